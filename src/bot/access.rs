@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use teloxide::types::{ChatId, UserId};
+
+/// 커맨드 실행에 필요한 권한
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Permission {
+    Member,
+    Admin,
+}
+
+/// 허용된 채팅/사용자와 관리자 목록
+pub struct AccessControl {
+    allowed: HashSet<i64>,
+    admins: HashSet<u64>,
+}
+
+impl AccessControl {
+
+    pub fn new(allowed: &[i64], admins: &[u64]) -> Self {
+        if allowed.is_empty() {
+            log::warn!("access allowlist is empty. every chat can use the bot");
+        }
+        Self {
+            allowed: allowed.iter().copied().collect(),
+            admins: admins.iter().copied().collect(),
+        }
+    }
+
+    pub fn is_admin(&self, user: Option<UserId>) -> bool {
+        user.is_some_and(|user| self.admins.contains(&user.0))
+    }
+
+    /// 허용 목록이 비어 있으면 모두 허용. 관리자는 항상 허용
+    pub fn is_allowed(&self, chat: Option<ChatId>, user: Option<UserId>) -> bool {
+        if self.allowed.is_empty() || self.is_admin(user) {
+            return true;
+        }
+        chat.is_some_and(|chat| self.allowed.contains(&chat.0))
+            || user.is_some_and(|user| self.allowed.contains(&(user.0 as i64)))
+    }
+
+    pub fn permits(&self, permission: Permission, user: Option<UserId>) -> bool {
+        match permission {
+            Permission::Member => true,
+            Permission::Admin => self.is_admin(user),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn allowlist_test() {
+        let access = AccessControl::new(&[-100, 7], &[1]);
+
+        assert!(access.is_allowed(Some(ChatId(-100)), Some(UserId(2))));
+        assert!(access.is_allowed(Some(ChatId(3)), Some(UserId(7))));
+        assert!(access.is_allowed(Some(ChatId(3)), Some(UserId(1))));
+        assert!(!access.is_allowed(Some(ChatId(3)), Some(UserId(2))));
+        assert!(!access.is_allowed(None, None));
+    }
+
+    #[test]
+    fn empty_allowlist_test() {
+        let access = AccessControl::new(&[], &[]);
+
+        assert!(access.is_allowed(Some(ChatId(3)), Some(UserId(2))));
+        assert!(!access.permits(Permission::Admin, Some(UserId(2))));
+    }
+
+    #[test]
+    fn admin_permission_test() {
+        let access = AccessControl::new(&[5], &[1]);
+
+        assert!(access.permits(Permission::Member, Some(UserId(5))));
+        assert!(!access.permits(Permission::Admin, Some(UserId(5))));
+        assert!(access.permits(Permission::Admin, Some(UserId(1))));
+        assert!(!access.permits(Permission::Admin, None));
+    }
+}
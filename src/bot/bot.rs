@@ -1,215 +1,559 @@
-use std::vec;
+use std::{ops::ControlFlow, vec};
 
 use teloxide::{
-    dispatching::{dialogue, dialogue::InMemStorage, UpdateHandler},
+    dispatching::{dialogue, dialogue::ErasedStorage, UpdateHandler},
     prelude::*,
-    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+    net::Download,
+    types::{UpdateKind, BotCommand, InlineKeyboardButton, InlineKeyboardMarkup, InlineQueryResult, InlineQueryResultArticle, InputFile, InputMessageContent, InputMessageContentText, User},
     utils::command::BotCommands,
 };
-use crate::{crawl::crawl::LolcheggCrawler, db::db::Storage};
-use std::sync::{Arc, RwLock};
+use crate::{crawl::{cache::DeckCache, crawl::LolcheggCrawler, error::CrawlError}, db::{db::{DoneRecord, Player, Storage}, error::StorageError}, monitor::{metrics::METRICS, server}};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use dptree::di::{DependencyMap, DependencySupplier};
+use serde::{Deserialize, Serialize};
+use tracing::Instrument;
 
-use super::traits::{self, Mode};
+use super::{access::{AccessControl, Permission}, digest::{self, Schedule}, error::{BotError, UserError}, i18n::{command_description, Lang, Text}, deck::{canonical_key, matches_query, normalize, valid_name, DeckMatcher}, leaderboard::rank, progress::{checklist, page_count, render_page, ChecklistItem}, stats::{is_special, summarize_placements, DoneStats}, traits::{self, Mode}, transfer::{self, ExportFormat, ExportRecord, MAX_IMPORT_BYTES}, watcher::{self, WatchOptions}, webhook::{self, WebhookOptions}};
 
-// todo. clone 대신 Arc로 wrapping 하는 것 고려
-#[derive(Clone)]
 pub struct LolcheBot {
     token: String,
     pub loader: LolcheggCrawler,
     pub stg: Storage,
+    dialogue: Arc<ErasedStorage<State>>,
+    access: Arc<AccessControl>,
+    watch: Option<WatchOptions>,
+    http: Option<SocketAddr>,
+    webhook: Option<WebhookOptions>,
 }
 
 impl LolcheBot {
 
-    pub fn new(token: String, loader:LolcheggCrawler, stg:Storage) -> Self{
+    pub fn new(token: String, loader:LolcheggCrawler, stg:Storage, dialogue: Arc<ErasedStorage<State>>, access: AccessControl) -> Self{
         Self{
             token:token,
             loader:loader,
             stg:stg,
+            dialogue:dialogue,
+            access:Arc::new(access),
+            watch:None,
+            http:None,
+            webhook:None,
         }
     }
 
-    pub async fn run(self) {
+    /// /healthz, /metrics 를 제공하는 HTTP 서버를 함께 실행
+    pub fn http(mut self, addr: SocketAddr) -> Self {
+        self.http = Some(addr);
+        self
+    }
+
+    /// long polling 대신 웹훅으로 업데이트를 받음
+    pub fn webhook(mut self, options: WebhookOptions) -> Self {
+        self.webhook = Some(options);
+        self
+    }
+
+    /// 메타 페이지 감시를 켬. 바뀐 점은 요약 구독 채팅에 알림
+    pub fn watch(mut self, options: WatchOptions) -> Self {
+        self.watch = Some(options);
+        self
+    }
+
+    /// 웹훅 등록처럼 업데이트를 받기 전에 실패하면 Err
+    pub async fn run(self) -> Result<(), BotError> {
         let bot = Bot::new(&self.token);
-        let shared_lolchebot = Arc::new(RwLock::new(self));
+        register_commands(&bot).await;
+        // memo. 핸들러끼리 잠금을 공유하지 않도록 서비스 단위로 주입
+        let crawler = Arc::new(self.loader);
+        let cache = Arc::new(DeckCache::new(Duration::from_secs(INLINE_CACHE_MINUTES * 60)));
 
-        Dispatcher::builder(
-            bot,
+        tokio::spawn(digest::run(bot.clone(), self.stg.clone(), crawler.clone()));
+        if let Some(options) = self.watch {
+            tokio::spawn(watcher::run(bot.clone(), self.stg.clone(), crawler.clone(), options));
+        }
+        if let Some(addr) = self.http {
+            tokio::spawn(server::serve(addr, self.stg.clone()));
+        }
+
+        let mut dispatcher = Dispatcher::builder(
+            bot.clone(),
             schema()
         )
-        .dependencies(dptree::deps![InMemStorage::<State>::new(), shared_lolchebot])
+        .dependencies(dptree::deps![self.dialogue, self.stg, crawler, cache, self.access])
         .enable_ctrlc_handler()
-        .build()
-        .dispatch()
-        .await;
+        .build();
+
+        match self.webhook {
+            Some(options) => {
+                let listener = webhook::listener(bot, &options).await?;
+                log::info!("receiving updates by webhook. listen: {}, url: {}", options.listen, options.url);
+                METRICS.set_dispatcher_running(true);
+                dispatcher.dispatch_with_listener(listener, LoggingErrorHandler::with_custom_text("An error from the webhook listener")).await;
+            }
+            None => {
+                METRICS.set_dispatcher_running(true);
+                dispatcher.dispatch().await;
+            }
+        }
+        METRICS.set_dispatcher_running(false);
+        Ok(())
     }
 }
 
-type MyDialogue = Dialogue<State, InMemStorage<State>>;
-type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+type MyDialogue = Dialogue<State, ErasedStorage<State>>;
+type HandlerResult = Result<(), BotError>;
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub enum State {
     #[default]
     Start,
-    Challenge,
-    Rollback,
+    Challenge { mode: Mode },
+    Rollback { mode: Mode },
+    ResetConfirm { mode: Mode },
+    Season,
+    Placement { mode: Mode, deck: String },
+    Completion { mode: Mode, deck: String, placement: u8 },
+    Progress { mode: Mode, items: Vec<ChecklistItem> },
+    Import,
 }
 
+/// /reset 이후 /undo_reset 으로 되돌릴 수 있는 시간
+const UNDO_RESET_MINUTES: u32 = 10;
+const RESET_CONFIRM: &str = "reset_confirm";
+const RESET_CANCEL: &str = "reset_cancel";
+const SKIP_PLACEMENT: &str = "skip_placement";
+const COMPLETE: &str = "complete";
+const INCOMPLETE: &str = "incomplete";
+const PAGE_NOOP: &str = "page_noop";
+/// 인라인 검색에 쓰는 덱 정보를 다시 크롤링하기까지의 시간
+const INLINE_CACHE_MINUTES: u64 = 10;
+/// 텔레그램이 허용하는 인라인 결과 최대 개수
+const MAX_INLINE_RESULTS: usize = 50;
+
 /// These commands are supported:
-#[derive(BotCommands, Clone)]
+#[derive(BotCommands, Clone, Debug)]
 #[command(rename_rule = "lowercase")]
 enum Command {
     /// Display this text.
     Help,
     #[command(description = "show current mode")]
     Mode,
-    #[command(description = "switch the mode")]
-    Switch,
-    #[command(description = "bring updated decks")]
-    Update,
+    #[command(description = "switch the mode, or set it. e.g. /switch pbe")]
+    Switch(String),
+    #[command(description = "bring updated decks, optionally of another mode. e.g. /update pbe")]
+    Update(String),
     #[command(description = "delete records")]
     Reset,
-    #[command(description = "show completed decks")]
-    Done,
+    #[command(rename = "undo_reset", description = "restore records deleted by the last reset")]
+    UndoReset,
+    #[command(description = "show completed decks, optionally only the latest n. e.g. /done 10")]
+    Done(String),
     #[command(description = "fix path to decks")]
-    Fix
+    Fix,
+    #[command(description = "close the current season and start a new one. e.g. /newseason Set14")]
+    NewSeason(String),
+    #[command(description = "browse completed decks of past seasons")]
+    Seasons,
+    #[command(description = "show placement statistics per deck")]
+    Placements,
+    #[command(description = "show completion statistics per mode")]
+    Stats,
+    #[command(description = "show done and remaining decks of the current meta")]
+    Progress,
+    #[command(description = "add a deck to the challenge list, or show the list. e.g. /challenge My deck")]
+    Challenge(String),
+    #[command(description = "remove a deck from the challenge list. e.g. /unchallenge My deck")]
+    Unchallenge(String),
+    #[command(description = "mark any deck as done. e.g. /markdone My deck")]
+    MarkDone(String),
+    #[command(description = "export completion history as csv or json. e.g. /export json")]
+    Export(String),
+    #[command(description = "import completion history from a csv or json file")]
+    Import,
+    #[command(description = "change the bot language. e.g. /lang ko")]
+    Lang(String),
+    #[command(description = "merge records of a renamed deck. e.g. /alias Old name => New name")]
+    Alias(String),
+    #[command(description = "rank members of this chat by decks completed in the current mode and season")]
+    Leaderboard,
+    #[command(description = "receive a daily or weekly digest. e.g. /subscribe 09:00 +09:00")]
+    Subscribe(String),
+    #[command(description = "stop the digest")]
+    Unsubscribe,
 }
 
-fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
+impl Command {
+    fn permission(&self) -> Permission {
+        match self {
+            Command::Reset | Command::UndoReset | Command::Fix | Command::NewSeason(_) | Command::Alias(_) | Command::Import => Permission::Admin,
+            _ => Permission::Member,
+        }
+    }
+}
+
+fn schema() -> UpdateHandler<BotError> {
     use dptree::case;
 
     let command_handler = teloxide::filter_command::<Command, _>()
+        .inspect(|update: Update| {
+            if let Some(name) = update_command(&update) {
+                METRICS.command(&name);
+            }
+        })
+        .branch(dptree::filter(|cmd: Command, msg: Message, access: Arc<AccessControl>| {
+            !access.permits(cmd.permission(), msg.from.as_ref().map(|user| user.id))
+        }).endpoint(forbidden))
         .branch(case![Command::Help].endpoint(help))
         .branch(case![Command::Mode].endpoint(mode))
-        .branch(case![Command::Switch].endpoint(switch))
-        .branch(case![Command::Update].endpoint(update))
+        .branch(case![Command::Switch(target)].endpoint(switch))
+        .branch(case![Command::Update(target)].endpoint(update))
         .branch(case![Command::Reset].endpoint(reset))
-        .branch(case![Command::Done].endpoint(done))
+        .branch(case![Command::UndoReset].endpoint(undo_reset))
+        .branch(case![Command::Done(count)].endpoint(done))
         .branch(case![Command::Fix].endpoint(fix))
+        .branch(case![Command::NewSeason(name)].endpoint(new_season))
+        .branch(case![Command::Seasons].endpoint(seasons))
+        .branch(case![Command::Placements].endpoint(placements))
+        .branch(case![Command::Stats].endpoint(stats))
+        .branch(case![Command::Progress].endpoint(progress))
+        .branch(case![Command::Challenge(name)].endpoint(challenge))
+        .branch(case![Command::Unchallenge(name)].endpoint(unchallenge))
+        .branch(case![Command::MarkDone(name)].endpoint(mark_done))
+        .branch(case![Command::Export(format)].endpoint(export))
+        .branch(case![Command::Import].endpoint(import))
+        .branch(case![Command::Lang(code)].endpoint(change_lang))
+        .branch(case![Command::Alias(input)].endpoint(alias))
+        .branch(case![Command::Leaderboard].endpoint(leaderboard))
+        .branch(case![Command::Subscribe(input)].endpoint(subscribe))
+        .branch(case![Command::Unsubscribe].endpoint(unsubscribe))
         .branch(dptree::endpoint(invalid_state))
 
         ;
 
     let message_handler = Update::filter_message()
+        .branch(case![State::Import].filter(|msg: Message| msg.document().is_some()).endpoint(import_file))
         .branch(command_handler);
 
     let callback_query_handler = Update::filter_callback_query()
-        .branch(case![State::Challenge].endpoint(success))
-        .branch(case![State::Rollback].endpoint(rollback))
+        .branch(case![State::Challenge { mode }].endpoint(success))
+        .branch(case![State::Rollback { mode }].endpoint(rollback))
+        .branch(case![State::ResetConfirm { mode }].endpoint(confirm_reset))
+        .branch(case![State::Season].endpoint(season_done))
+        .branch(case![State::Placement { mode, deck }].endpoint(placement))
+        .branch(case![State::Completion { mode, deck, placement }].endpoint(completion))
+        .branch(case![State::Progress { mode, items }].endpoint(turn_page))
     ;
 
-    dialogue::enter::<Update, InMemStorage<State>, State, _>()
-        .branch(message_handler)
-        .branch(callback_query_handler)
-        
-}
-
-/*
-Todo
-Error handling 함수
-*/
-// async fn error_handler<H, Fut> (
-//     handler: H,
-//     bot: Bot,
-//     msg: Message,
-// ) -> HandlerResult
-// where
-//     H: Fn(Bot, Message) -> Fut + Send + Sync + 'static,
-//     Fut: std::future::Future<Output = HandlerResult> + Send,
-// {
-//     match handler(bot, msg).await {
-//         Ok(_) => Ok(()),
-//         Err(e) => {
-//             .await;
-//             Ok(()) // Prevents the error from propagating further.,\
-//         },
-//     }
-// }
-
-
-async fn help(bot: Bot,  msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id, Command::descriptions().to_string()).await?;
-    Ok(())
-}
-
-async fn mode(bot: Bot, lolche_bot:Arc<RwLock<LolcheBot>>, msg: Message) -> HandlerResult {
+    with_context()
+        .chain(report_error())
+        .chain(dptree::map_async(chat_lang)
+            .branch(dptree::filter(|update: Update, access: Arc<AccessControl>| {
+                !access.is_allowed(update.chat().map(|chat| chat.id), update.from().map(|user| user.id))
+            }).endpoint(unauthorized))
+            .branch(Update::filter_inline_query().endpoint(inline_query))
+            .branch(dialogue::enter::<Update, ErasedStorage<State>, State, _>()
+                .branch(message_handler)
+                .branch(callback_query_handler)
+            )
+        )
+}
+
+/// 하위 핸들러의 로그에 채팅, 사용자, 커맨드, 모드를 함께 남기도록 span으로 감쌈
+fn with_context() -> UpdateHandler<BotError> {
+    dptree::from_fn(|deps: DependencyMap, cont: dptree::Cont<'static, DependencyMap, HandlerResult>| async move {
+        let update: Arc<Update> = deps.get();
+
+        // memo. mode는 모드를 조회하는 핸들러에서 기록
+        let span = tracing::info_span!(
+            "update",
+            update_id = update.id.0,
+            chat_id = update.chat().map(|chat| chat.id.0),
+            user_id = update.from().map(|user| user.id.0),
+            command = update_command(&update).as_deref(),
+            mode = tracing::field::Empty,
+        );
+        cont(deps).instrument(span).await
+    })
+}
+
+/// 현재 모드를 조회하고 update span에 기록
+async fn current_mode(stg: &Storage) -> Result<Mode, StorageError> {
+    let mode = stg.blocking(Storage::select_mode).await?;
+    record_mode(&mode);
+    Ok(mode)
+}
+
+/// 모드를 읽는 핸들러에서만 기록하여 모든 업데이트마다 DB를 조회하지 않도록 함
+fn record_mode(mode: &Mode) {
+    tracing::Span::current().record("mode", tracing::field::debug(mode));
+}
+
+/// 메시지는 /로 시작하는 첫 단어에서 /와 @봇이름을 뺀 커맨드 이름, 콜백과 인라인 검색은 종류 이름
+/// 예) /undo_reset@lolche_bot -> undo_reset
+fn update_command(update: &Update) -> Option<String> {
+    match &update.kind {
+        UpdateKind::Message(msg) => msg.text()
+            .and_then(|text| text.split_whitespace().next())
+            .and_then(|word| word.strip_prefix('/'))
+            .map(|word| word.split('@').next().unwrap_or(word).to_lowercase()),
+        UpdateKind::CallbackQuery(_) => Some(String::from("callback")),
+        UpdateKind::InlineQuery(_) => Some(String::from("inline")),
+        _ => None,
+    }
+}
+
+/// 하위 핸들러가 Err를 반환하면 로그를 남기고, 요청이 들어온 채팅에 오류 종류별 메시지로 응답
+fn report_error() -> UpdateHandler<BotError> {
+    dptree::from_fn(|deps: DependencyMap, cont: dptree::Cont<'static, DependencyMap, HandlerResult>| async move {
+        let update: Arc<Update> = deps.get();
+        let bot: Arc<Bot> = deps.get();
+        let stg: Arc<Storage> = deps.get();
+
+        match cont(deps).await {
+            ControlFlow::Break(Err(error)) => {
+                let chat_id = update.chat().map(|chat| chat.id);
+                log::error!("handler failed. update: {}, chat: {:?}, error: {}", update.id.0, chat_id, error);
+
+                if let Some(chat_id) = chat_id {
+                    let lang = chat_lang((*update).clone(), (*stg).clone()).await;
+                    if let Err(e) = bot.send_message(chat_id, error.msg(lang)).await {
+                        log::error!("fail to report error to chat {}. {}", chat_id, e);
+                    }
+                }
+                ControlFlow::Break(Ok(()))
+            }
+            flow => flow,
+        }
+    })
+}
+
+
+/// 텔레그램 클라이언트의 커맨드 메뉴 등록. 실패해도 봇은 계속 동작
+async fn register_commands(bot: &Bot) {
+    if let Err(e) = bot.set_my_commands(Command::bot_commands()).await {
+        log::warn!("fail to register commands. {}", e);
+    }
+
+    let korean = Command::bot_commands()
+        .into_iter()
+        .map(|cmd| match command_description(Lang::Ko, &cmd.command) {
+            Some(description) => BotCommand::new(cmd.command, description),
+            None => cmd,
+        })
+        .collect::<Vec<BotCommand>>();
+    if let Err(e) = bot.set_my_commands(korean).language_code(Lang::Ko.code()).await {
+        log::warn!("fail to register korean commands. {}", e);
+    }
+}
+
+/// 채팅별 언어 설정. 조회에 실패하면 기본 언어로 응답
+async fn chat_lang(update: Update, stg: Storage) -> Lang {
+    // memo. 인라인 검색처럼 채팅이 없는 요청은 사용자의 텔레그램 언어를 따름
+    let Some(chat) = update.chat() else {
+        return update.from()
+            .and_then(|user| user.language_code.as_deref())
+            .and_then(Lang::parse)
+            .unwrap_or_default();
+    };
+    let chat_id = chat.id;
+    stg.blocking(move |stg| stg.select_lang(chat_id.0)).await.unwrap_or_else(|e| {
+        log::warn!("fail to load language of chat {}. {}", chat_id, e);
+        Lang::default()
+    })
+}
+
+async fn help(bot: Bot,  msg: Message, lang: Lang) -> HandlerResult {
+    let text = match lang {
+        Lang::En => Command::descriptions().to_string(),
+        // memo. 한국어 설명이 없는 커맨드는 영어 설명을 그대로 보여줌
+        _ => Command::bot_commands()
+            .into_iter()
+            .map(|cmd| format!("{} — {}", cmd.command, command_description(lang, &cmd.command).unwrap_or(&cmd.description)))
+            .collect::<Vec<String>>()
+            .join("\n"),
+    };
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+async fn change_lang(bot: Bot, msg: Message, code: String, stg: Storage, lang: Lang) -> HandlerResult {
+
+    if code.trim().is_empty() {
+        bot.send_message(msg.chat.id, Text::CurrentLang(lang).render(lang)).await?;
+        return Ok(());
+    }
+
+    let lang = Lang::parse(&code).ok_or(UserError::from(Text::InvalidLang))?;
+    let chat_id = msg.chat.id.0;
+    stg.blocking(move |stg| stg.upsert_lang(chat_id, lang)).await?;
+
+    bot.send_message(msg.chat.id, Text::LangChanged(lang).render(lang)).await?;
+    Ok(())
+}
+
+async fn mode(bot: Bot, stg: Storage, msg: Message, lang: Lang) -> HandlerResult {
     
-    let mode = lolche_bot.read().unwrap().stg.select_mode()?;
+    let mode = current_mode(&stg).await?;
 
-    bot.send_message(msg.chat.id, format!("현재 모드 : {}", mode.msg())).await?;
+    bot.send_message(msg.chat.id, Text::CurrentMode(mode).render(lang)).await?;
     
     Ok(())
 }
 
-async fn switch(bot: Bot, dialogue: MyDialogue, lolche_bot:Arc<RwLock<LolcheBot>>, msg: Message) -> HandlerResult {
-    // todo. DB에서 현재 모드 변경
-    let mode = lolche_bot.read().unwrap().stg.select_mode()?.switch();
+async fn switch(bot: Bot, stg: Storage, msg: Message, target: String, lang: Lang) -> HandlerResult {
+    // memo. 모드를 지정하지 않으면 현재 모드의 반대로 전환
+    let mode = match target.trim() {
+        "" => stg.blocking(Storage::select_mode).await?.switch(),
+        target => Mode::parse(target).ok_or(UserError::from(Text::InvalidMode))?,
+    };
     
-    lolche_bot.read().unwrap().stg.upsert_mode(&mode)?;
+    let target = mode.clone();
+    stg.blocking(move |stg| stg.upsert_mode(&target)).await?;
     
-    bot.send_message(msg.chat.id, format!("모드 변경 성공. 현재 모드 : {}", mode.msg())).await?;
+    bot.send_message(msg.chat.id, Text::ModeSwitched(mode).render(lang)).await?;
     
     Ok(())
 }
 
-async fn update(bot: Bot, msg: Message, dialogue: MyDialogue, lolche_bot:Arc<RwLock<LolcheBot>>) -> HandlerResult {
+async fn update(bot: Bot, msg: Message, dialogue: MyDialogue, stg: Storage, crawler: Arc<LolcheggCrawler>, target: String, lang: Lang) -> HandlerResult {
     
-    let mode = lolche_bot.read().unwrap().stg.select_mode()?;
+    // memo. 모드를 지정하면 현재 모드를 바꾸지 않고 해당 모드의 목록을 보여줌
+    let mode = match target.trim() {
+        "" => stg.blocking(Storage::select_mode).await?,
+        target => Mode::parse(target).ok_or(UserError::from(Text::InvalidMode))?,
+    };
+    record_mode(&mode);
 
-    let done = lolche_bot.read().unwrap().stg.retrieve_done(&mode)?;
+    let (target, user_id) = (mode.clone(), sender(&msg)?.id.0);
+    let (done, custom, aliases) = stg.blocking(move |stg| {
+        Ok((stg.retrieve_done(&target, user_id)?, stg.retrieve_custom_decks(&target, user_id)?, stg.retrieve_aliases()?))
+    }).await?;
+    let matcher = DeckMatcher::new(aliases);
 
     // todo 이렇게 옮기는거 말고 copy 해서 넘길 순 없나??
-    let deck_result = tokio::task::spawn_blocking(move || {
-        lolche_bot.read().unwrap().loader.recommended_deck(&mode)
+    let target = mode.clone();
+    let updated_deck = tokio::task::spawn_blocking(move || {
+        load_deck(&crawler, &target)
     })
-    .await?;
+    .await??;
 
-    if deck_result.is_err() {
-        bot.send_message(msg.chat.id, format!("오류 발생. {:?}", deck_result)).await?;
-        return Ok(());
-    }
-
-    let updated_deck = deck_result.unwrap();
-    let [normal, special] = todo_deck(updated_deck, done);
+    let challenges = todo_challenge(custom, &updated_deck, &done, &matcher);
+    let [normal, special] = todo_deck(updated_deck, done, &matcher);
 
     log::info!("{:?}", normal);
     log::info!("{:?}", special);
 
-    bot.send_message(msg.chat.id, "다음 일반 덱")
+    bot.send_message(msg.chat.id, Text::NextNormalDeck.render(lang))
     .reply_markup(InlineKeyboardMarkup::new(
         normal.into_iter().map(|s| vec![InlineKeyboardButton::callback(s.clone(), s)]).collect::<Vec<Vec<InlineKeyboardButton>>>()
         ))
     .await?;
 
-    bot.send_message(msg.chat.id, "잔여 특수 덱")
+    bot.send_message(msg.chat.id, Text::RemainingSpecialDeck.render(lang))
     .reply_markup(InlineKeyboardMarkup::new(
         special.into_iter().map(|s| vec![InlineKeyboardButton::callback(s.clone(), s)]).collect::<Vec<Vec<InlineKeyboardButton>>>()
         ))
     .await?;
 
-    dialogue.update(State::Challenge).await?;
+    if !challenges.is_empty() {
+        bot.send_message(msg.chat.id, Text::RemainingChallenge.render(lang))
+        .reply_markup(InlineKeyboardMarkup::new(
+            challenges.into_iter().map(|s| vec![InlineKeyboardButton::callback(s.clone(), s)]).collect::<Vec<Vec<InlineKeyboardButton>>>()
+            ))
+        .await?;
+    }
+
+    dialogue.update(State::Challenge { mode }).await?;
     Ok(())
 }
 
-async fn reset(bot: Bot, msg: Message, lolche_bot:Arc<RwLock<LolcheBot>>) -> HandlerResult {
+async fn reset(bot: Bot, msg: Message, dialogue: MyDialogue, stg: Storage, lang: Lang) -> HandlerResult {
     
-    let mode = lolche_bot.read().unwrap().stg.select_mode()?;
+    let mode = current_mode(&stg).await?;
 
-    lolche_bot.read().unwrap().stg.delete_all(&mode)?;
+    let target = mode.clone();
+    let count = stg.blocking(move |stg| stg.count_done(&target)).await?;
+    if count == 0 {
+        bot.send_message(msg.chat.id, Text::NothingToReset(mode).render(lang)).await?;
+        return Ok(());
+    }
+
+    bot.send_message(msg.chat.id, Text::ResetConfirm { mode: mode.clone(), count, minutes: UNDO_RESET_MINUTES }.render(lang))
+    .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(Text::ResetButton(count).render(lang), RESET_CONFIRM),
+        InlineKeyboardButton::callback(Text::Cancel.render(lang), RESET_CANCEL),
+    ]]))
+    .await?;
 
-    bot.send_message(msg.chat.id, format!("모드 {}에 대한 이력 삭제 완료", mode.msg())).await?;
+    dialogue.update(State::ResetConfirm { mode }).await?;
+    Ok(())
+}
+
+async fn confirm_reset(bot: Bot,
+                dialogue: MyDialogue,
+                q: CallbackQuery,
+                mode: Mode,
+                stg: Storage,
+                access: Arc<AccessControl>,
+                lang: Lang) -> HandlerResult
+{
+    // memo. 콜백은 커맨드 권한 검사를 거치지 않으므로 누른 사용자를 다시 확인
+    if !access.is_admin(Some(q.from.id)) {
+        log::warn!("reset confirm refused. chat: {}, user: {}", dialogue.chat_id(), q.from.id);
+        bot.send_message(dialogue.chat_id(), Text::AdminOnlyReset.render(lang)).await?;
+        return Ok(());
+    }
+
+    if q.data.as_deref() != Some(RESET_CONFIRM) {
+        bot.send_message(dialogue.chat_id(), Text::ResetCanceled.render(lang)).await?;
+        dialogue.exit().await?;
+        return Ok(());
+    }
+
+    let target = mode.clone();
+    let archived = stg.blocking(move |stg| stg.archive_all(&target)).await?;
+
+    bot.send_message(dialogue.chat_id(), Text::ResetDone { mode, count: archived, minutes: UNDO_RESET_MINUTES }.render(lang)).await?;
+    dialogue.exit().await?;
+    Ok(())
+}
+
+async fn undo_reset(bot: Bot, msg: Message, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let mode = current_mode(&stg).await?;
+
+    let target = mode.clone();
+    let restored = stg.blocking(move |stg| stg.restore_archive(&target, UNDO_RESET_MINUTES)).await?;
+    if restored == 0 {
+        bot.send_message(msg.chat.id, Text::NothingToUndo { mode, minutes: UNDO_RESET_MINUTES }.render(lang)).await?;
+        return Ok(());
+    }
+
+    bot.send_message(msg.chat.id, Text::UndoDone { mode, count: restored }.render(lang)).await?;
     Ok(())
 }
 
 // memo. iter-map 안에서는 비동기를 날리지 못 함
-async fn done(bot: Bot, dialogue: MyDialogue, msg: Message, lolche_bot:Arc<RwLock<LolcheBot>>) -> HandlerResult {
+async fn done(bot: Bot, dialogue: MyDialogue, msg: Message, stg: Storage, count: String, lang: Lang) -> HandlerResult {
     
-    let mode = lolche_bot.read().unwrap().stg.select_mode()?;
-
-    let done = lolche_bot.read().unwrap().stg.retrieve_done(&mode)?;
+    // memo. 그룹에서는 커맨드를 보낸 사용자의 기록만 보여줌
+    let user_id = sender(&msg)?.id.0;
+    let (mode, mut done) = stg.blocking(move |stg| {
+        let mode = stg.select_mode()?;
+        let done = stg.retrieve_done(&mode, user_id)?;
+        Ok((mode, done))
+    }).await?;
+    record_mode(&mode);
+    // memo. 개수를 지정하면 최근 기록만 보여줌
+    if !count.trim().is_empty() {
+        let count = count.trim().parse::<usize>()
+            .ok()
+            .filter(|count| *count > 0)
+            .ok_or(UserError::from(Text::InvalidDoneCount))?;
+        done = done.split_off(done.len().saturating_sub(count));
+    }
     // 버튼 보내기
-    bot.send_message(msg.chat.id, "완료 내역")
+    bot.send_message(msg.chat.id, Text::DoneList.render(lang))
        .reply_markup(
             InlineKeyboardMarkup::new(
                 done.iter()
@@ -217,75 +561,647 @@ async fn done(bot: Bot, dialogue: MyDialogue, msg: Message, lolche_bot:Arc<RwLoc
                     .collect::<Vec<Vec<InlineKeyboardButton>>>()
         ))
        .await?;
-    dialogue.update(State::Rollback).await?;
+    dialogue.update(State::Rollback { mode }).await?;
     Ok(())
 }
 
-async fn fix(bot: Bot, msg: Message, lolche_bot:Arc<RwLock<LolcheBot>>) -> HandlerResult {
+async fn fix(bot: Bot, msg: Message, crawler: Arc<LolcheggCrawler>, lang: Lang) -> HandlerResult {
     
     tokio::task::spawn_blocking(move || {
-        let mut mutable_bot = lolche_bot.write().unwrap();
-        mutable_bot.loader.update_css_path();
+        crawler.update_css_path()
     })
-    .await?;
+    .await??;
     
-    bot.send_message(msg.chat.id, "css path 수정 완료").await?;
+    bot.send_message(msg.chat.id, Text::PathFixed.render(lang)).await?;
+    Ok(())
+}
+
+async fn new_season(bot: Bot, msg: Message, name: String, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 30 {
+        Err(UserError::from(Text::InvalidSeasonName))?
+    }
+
+    let target = name.to_string();
+    let (previous, archived) = stg.blocking(move |stg| Ok((stg.current_season()?, stg.start_season(&target)?))).await?;
+
+    let previous = previous.map(|season| season.name).unwrap_or(Text::PreviousSeason.render(lang));
+    bot.send_message(msg.chat.id, Text::SeasonStarted { previous, archived, name: name.to_string() }.render(lang)).await?;
+    Ok(())
+}
+
+async fn seasons(bot: Bot, dialogue: MyDialogue, msg: Message, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let (current, past) = stg.blocking(|stg| Ok((stg.current_season()?, stg.past_seasons()?))).await?;
+    let current = current
+        .map(|season| Text::CurrentSeason { name: season.name, started_at: season.started_at }.render(lang))
+        .unwrap_or(Text::NoCurrentSeason.render(lang));
+
+    if past.is_empty() {
+        bot.send_message(msg.chat.id, format!("{}\n{}", current, Text::NoPastSeason.render(lang))).await?;
+        return Ok(());
+    }
+
+    bot.send_message(msg.chat.id, format!("{}\n{}", current, Text::PastSeasons.render(lang)))
+       .reply_markup(
+            InlineKeyboardMarkup::new(
+                past.iter()
+                    .map(|season| vec![InlineKeyboardButton::callback(
+                        format!("{} ({} ~ {})", season.name, season.started_at, season.ended_at.as_deref().unwrap_or_default()),
+                        season.id.to_string(),
+                    )])
+                    .collect::<Vec<Vec<InlineKeyboardButton>>>()
+        ))
+       .await?;
+    dialogue.update(State::Season).await?;
+    Ok(())
+}
+
+async fn season_done(bot: Bot,
+                dialogue: MyDialogue,
+                q: CallbackQuery,
+                stg: Storage,
+                lang: Lang) -> HandlerResult
+{
+    let id = q.data.as_ref()
+        .and_then(|data| data.parse::<u32>().ok())
+        .ok_or(UserError::from(Text::SeasonNotFound))?;
+    let season = stg.blocking(Storage::past_seasons).await?
+        .into_iter()
+        .find(|season| season.id == id)
+        .ok_or(UserError::from(Text::SeasonNotFound))?;
+
+    let mut text = format!("{} ({} ~ {})", Text::SeasonTitle(season.name).render(lang), season.started_at, season.ended_at.unwrap_or_default());
+    for mode in [Mode::main, Mode::pbe] {
+        let (season_id, target) = (season.id, mode.clone());
+        let done = stg.blocking(move |stg| stg.retrieve_season_done(season_id, &target)).await?;
+        text.push_str(&format!("\n\n{}", Text::ModeCount { mode, count: done.len() }.render(lang)));
+        for deck in done {
+            text.push_str(&format!("\n- {}", deck));
+        }
+    }
+
+    bot.send_message(dialogue.chat_id(), text).await?;
+    dialogue.exit().await?;
     Ok(())
 }
 
-async fn invalid_state(bot: Bot, msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id, "잘못된 커맨드").await?;
+// memo. 일반 메시지나 버튼마다 답하면 허용되지 않은 그룹에 응답이 쌓이므로 커맨드에만 안내
+async fn unauthorized(bot: Bot, update: Update, lang: Lang) -> HandlerResult {
+    let chat_id = update.chat().map(|chat| chat.id);
+    let user_id = update.from().map(|user| user.id);
+
+    let command = match &update.kind {
+        UpdateKind::Message(_) => update_command(&update),
+        _ => None,
+    };
+    let Some(command) = command else {
+        log::info!("ignored update from unauthorized chat. chat: {:?}, user: {:?}", chat_id, user_id);
+        return Ok(());
+    };
+
+    log::warn!("unauthorized access. chat: {:?}, user: {:?}, command: {}", chat_id, user_id, command);
+    if let Some(chat_id) = chat_id {
+        bot.send_message(chat_id, Text::Unauthorized.render(lang)).await?;
+    }
+    Ok(())
+}
+
+async fn forbidden(bot: Bot, msg: Message, cmd: Command, lang: Lang) -> HandlerResult {
+    log::warn!("admin command refused. chat: {}, user: {:?}, command: {:?}", msg.chat.id, msg.from.as_ref().map(|user| user.id), cmd);
+    bot.send_message(msg.chat.id, Text::Forbidden.render(lang)).await?;
+    Ok(())
+}
+
+async fn invalid_state(bot: Bot, msg: Message, lang: Lang) -> HandlerResult {
+    bot.send_message(msg.chat.id, Text::InvalidCommand.render(lang)).await?;
     Ok(())
 }
 
 async fn success(bot: Bot, 
                 dialogue: MyDialogue,
                 q: CallbackQuery, 
-                lolche_bot:Arc<RwLock<LolcheBot>>) -> HandlerResult 
+                mode: Mode,
+                lang: Lang) -> HandlerResult 
 {
-    if let Some(deck) = &q.data {
-        
-        let mode = lolche_bot.read().unwrap().stg.select_mode()?; 
+    let deck = q.data.as_ref().ok_or(UserError::from(Text::DeckNotFound))?;
 
-        lolche_bot.read().unwrap().stg.record_done(deck, &mode)?;
+    let row = |placements: std::ops::RangeInclusive<u8>| placements
+        .map(|p| InlineKeyboardButton::callback(Text::PlacementButton(p).render(lang), p.to_string()))
+        .collect::<Vec<InlineKeyboardButton>>();
+
+    bot.send_message(dialogue.chat_id(), Text::AskPlacement(deck.clone()).render(lang))
+    .reply_markup(InlineKeyboardMarkup::new(vec![
+        row(1..=4),
+        row(5..=8),
+        vec![InlineKeyboardButton::callback(Text::SkipPlacement.render(lang), SKIP_PLACEMENT)],
+    ]))
+    .await?;
 
-        bot.send_message( dialogue.chat_id(), format!("{} 완료!", deck)).await?;
+    dialogue.update(State::Placement { mode, deck: deck.clone() }).await?;
+    Ok(())
+}
+
+async fn placement(bot: Bot,
+                dialogue: MyDialogue,
+                q: CallbackQuery,
+                (mode, deck): (Mode, String),
+                stg: Storage,
+                lang: Lang) -> HandlerResult
+{
+    if q.data.as_deref() == Some(SKIP_PLACEMENT) {
+        let (target, name, player) = (mode.clone(), deck.clone(), player(dialogue.chat_id(), &q.from));
+        stg.blocking(move |stg| stg.record_done(&name, &target, None, &player)).await?;
+        bot.send_message(dialogue.chat_id(), Text::Completed(deck).render(lang)).await?;
         dialogue.exit().await?;
+        return Ok(());
+    }
+
+    let placement = q.data.as_ref()
+        .and_then(|data| data.parse::<u8>().ok())
+        .filter(|p| (1..=8).contains(p))
+        .ok_or(UserError::from(Text::InvalidPlacement))?;
+
+    bot.send_message(dialogue.chat_id(), Text::AskCompletion { deck: deck.clone(), placement }.render(lang))
+    .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(Text::Complete.render(lang), COMPLETE),
+        InlineKeyboardButton::callback(Text::Incomplete.render(lang), INCOMPLETE),
+    ]]))
+    .await?;
+
+    dialogue.update(State::Completion { mode, deck, placement }).await?;
+    Ok(())
+}
+
+async fn completion(bot: Bot,
+                dialogue: MyDialogue,
+                q: CallbackQuery,
+                (mode, deck, placement): (Mode, String, u8),
+                stg: Storage,
+                lang: Lang) -> HandlerResult
+{
+    let completed = q.data.as_deref() == Some(COMPLETE);
+    // memo. 그룹에서는 버튼을 누른 사용자의 기록으로 남김
+    let player = player(dialogue.chat_id(), &q.from);
+
+    let (target, name) = (mode.clone(), deck.clone());
+    stg.blocking(move |stg| {
+        stg.record_game(&name, &target, placement, completed, &player)?;
+        if completed {
+            stg.record_done(&name, &target, Some(placement), &player)?;
+        }
+        Ok(())
+    }).await?;
+
+    if completed {
+        bot.send_message(dialogue.chat_id(), Text::CompletedWithPlacement { deck, placement }.render(lang)).await?;
+    } else {
+        bot.send_message(dialogue.chat_id(), Text::GameRecorded { deck, placement }.render(lang)).await?;
     }
+    dialogue.exit().await?;
+    Ok(())
+}
+
+async fn placements(bot: Bot, msg: Message, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let user_id = sender(&msg)?.id.0;
+    let (mode, games) = stg.blocking(move |stg| {
+        let mode = stg.select_mode()?;
+        let games = stg.retrieve_games(&mode, user_id)?;
+        Ok((mode, games))
+    }).await?;
+    record_mode(&mode);
+
+    let summary = summarize_placements(&games);
+    if summary.is_empty() {
+        bot.send_message(msg.chat.id, Text::NoGames(mode).render(lang)).await?;
+        return Ok(());
+    }
+
+    let mut text = Text::PlacementTitle(mode).render(lang);
+    for deck in summary {
+        let complete = deck.games_to_complete
+            .map(|games| Text::GamesToComplete(games).render(lang))
+            .unwrap_or(Text::Incomplete.render(lang));
+        let summary = Text::PlacementSummary { average: deck.average, top4_rate: deck.top4_rate, games: deck.games }.render(lang);
+        text.push_str(&format!("\n\n{}\n{} · {}", deck.name, summary, complete));
+    }
+
+    bot.send_message(msg.chat.id, text).await?;
     Ok(())
 }
 
 async fn rollback(bot: Bot, 
                 dialogue: MyDialogue,
                 q: CallbackQuery, 
-                lolche_bot:Arc<RwLock<LolcheBot>>) -> HandlerResult 
+                mode: Mode,
+                stg: Storage,
+                lang: Lang) -> HandlerResult 
 {
-    if let Some(deck) = &q.data {
-        let mode = lolche_bot.read().unwrap().stg.select_mode()?; 
-        lolche_bot.read().unwrap().stg.delete_record(&mode, deck)?;
-        bot.send_message(dialogue.chat_id(), format!("{} 롤백 완료", deck)).await?;
-        dialogue.exit().await?;
+    let deck = q.data.as_ref().ok_or(UserError::from(Text::DeckNotFound))?;
+
+    // memo. 목록을 보여준 뒤 모드가 바뀌어도 목록을 가져온 모드에서 삭제
+    let (target, user_id) = (deck.clone(), q.from.id.0);
+    stg.blocking(move |stg| stg.delete_record(&mode, &target, Some(user_id))).await?;
+    bot.send_message(dialogue.chat_id(), Text::RolledBack(deck.clone()).render(lang)).await?;
+    dialogue.exit().await?;
+    Ok(())
+}
+
+async fn stats(bot: Bot, msg: Message, stg: Storage, crawler: Arc<LolcheggCrawler>, lang: Lang) -> HandlerResult {
+
+    let user_id = sender(&msg)?.id.0;
+    let today = chrono::Local::now().date_naive();
+    let mut text = Text::StatsTitle.render(lang);
+    let matcher = DeckMatcher::new(stg.blocking(Storage::retrieve_aliases).await?);
+
+    for mode in [Mode::main, Mode::pbe] {
+        let target = mode.clone();
+        let records = stg.blocking(move |stg| stg.retrieve_done_records(&target, Some(user_id))).await?;
+
+        let (loader, target) = (crawler.clone(), mode.clone());
+        // memo. 메타 조회 실패 시에도 나머지 통계는 보여줌
+        let meta = tokio::task::spawn_blocking(move || load_deck(&loader, &target))
+            .await?
+            .map_err(|e| log::warn!("fail to load meta for stats. {}", e))
+            .ok();
+
+        let stats = DoneStats::new(&records, meta.as_deref(), &matcher, today);
+
+        let meta_progress = match stats.meta_progress {
+            Some((_, 0)) | None => Text::LoadFailed.render(lang),
+            Some((done, total)) => format!("{}/{} ({:.0}%)", done, total, done as f64 / total as f64 * 100.0),
+        };
+
+        let summary = Text::ModeStats {
+            total: stats.total,
+            this_week: stats.this_week,
+            meta_progress,
+            normal: stats.normal,
+            special: stats.special,
+            streak: stats.longest_streak,
+        };
+        text.push_str(&format!("\n\n[{}]\n{}", mode.msg(lang), summary.render(lang)));
     }
+
+    bot.send_message(msg.chat.id, text).await?;
     Ok(())
 }
 
-async fn error_handle(bot: Bot,  dialogue: MyDialogue, result: HandlerResult) ->HandlerResult{
+async fn progress(bot: Bot, dialogue: MyDialogue, msg: Message, stg: Storage, crawler: Arc<LolcheggCrawler>, lang: Lang) -> HandlerResult {
+
+    let user_id = sender(&msg)?.id.0;
+    let (mode, done, aliases) = stg.blocking(move |stg| {
+        let mode = stg.select_mode()?;
+        let done = stg.retrieve_done(&mode, user_id)?;
+        Ok((mode, done, stg.retrieve_aliases()?))
+    }).await?;
+    record_mode(&mode);
+    let matcher = DeckMatcher::new(aliases);
 
-    match result {
-        Ok(_) => {},
-        Err(error) => {bot.send_message(dialogue.chat_id(), format!("오류 발생 : {}", error )).await?;},
+    let target = mode.clone();
+    let meta = tokio::task::spawn_blocking(move || {
+        load_deck(&crawler, &target)
+    })
+    .await??;
+
+    let items = checklist(&meta, &done, &matcher);
+    let text = render_page(&Text::ProgressTitle(mode.clone()).render(lang), &items, 0, lang);
+
+    let pages = page_count(&items);
+    if pages > 1 {
+        bot.send_message(msg.chat.id, text).reply_markup(page_keyboard(0, pages, lang)).await?;
+    } else {
+        bot.send_message(msg.chat.id, text).await?;
     }
+
+    dialogue.update(State::Progress { mode, items }).await?;
     Ok(())
 }
 
-fn todo_deck (mut recom : Vec<String> , done : Vec<String>) -> [Vec<String>;2] {
-    use std::collections::HashMap;
+// memo. 새 메시지 대신 기존 메시지를 수정하여 페이지 이동
+async fn turn_page(bot: Bot,
+                q: CallbackQuery,
+                (mode, items): (Mode, Vec<ChecklistItem>),
+                lang: Lang) -> HandlerResult
+{
+    bot.answer_callback_query(q.id.clone()).await?;
 
-    let mut done_map: HashMap<String, bool> = HashMap::new();
-    // todo. String 말고 reference?
-    for d in done {
-        done_map.insert(d, true);
+    let Some(page) = q.data.as_ref().and_then(|data| data.parse::<usize>().ok()) else {
+        return Ok(());
+    };
+    let message = q.message.as_ref().ok_or(UserError::from(Text::PageNotFound))?;
+
+    let pages = page_count(&items);
+    let page = page.min(pages - 1);
+    bot.edit_message_text(message.chat().id, message.id(), render_page(&Text::ProgressTitle(mode).render(lang), &items, page, lang))
+       .reply_markup(page_keyboard(page, pages, lang))
+       .await?;
+    Ok(())
+}
+
+fn page_keyboard(page: usize, pages: usize, lang: Lang) -> InlineKeyboardMarkup {
+    let mut row = Vec::new();
+    if page > 0 {
+        row.push(InlineKeyboardButton::callback(Text::PreviousPage.render(lang), (page - 1).to_string()));
+    }
+    row.push(InlineKeyboardButton::callback(format!("{}/{}", page + 1, pages), PAGE_NOOP));
+    if page + 1 < pages {
+        row.push(InlineKeyboardButton::callback(Text::NextPage.render(lang), (page + 1).to_string()));
     }
+    InlineKeyboardMarkup::new(vec![row])
+}
+
+async fn challenge(bot: Bot, msg: Message, name: String, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let mode = current_mode(&stg).await?;
+
+    let user_id = sender(&msg)?.id.0;
+
+    if name.trim().is_empty() {
+        let target = mode.clone();
+        let list = stg.blocking(move |stg| stg.retrieve_custom_decks(&target, user_id)).await?;
+        let text = if list.is_empty() {
+            Text::EmptyChallenge(mode).render(lang)
+        } else {
+            format!("{}\n{}", Text::ChallengeList(mode).render(lang), list.join("\n"))
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let name = deck_name(&name)?;
+    let (target, deck) = (mode.clone(), name.clone());
+    let text = if stg.blocking(move |stg| stg.add_custom_deck(&target, &deck, user_id)).await? {
+        Text::ChallengeAdded { mode, deck: name }.render(lang)
+    } else {
+        Text::ChallengeExists { mode, deck: name }.render(lang)
+    };
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+async fn unchallenge(bot: Bot, msg: Message, name: String, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let mode = current_mode(&stg).await?;
+    let name = deck_name(&name)?;
+    let user_id = sender(&msg)?.id.0;
+
+    let (target, deck) = (mode.clone(), name.clone());
+    let text = if stg.blocking(move |stg| stg.delete_custom_deck(&target, &deck, user_id)).await? {
+        Text::ChallengeRemoved { mode, deck: name }.render(lang)
+    } else {
+        Text::ChallengeMissing { mode, deck: name }.render(lang)
+    };
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+// memo. 크롤링 목록에 없는 덱도 완료로 기록할 수 있도록 등수 없이 바로 기록
+async fn mark_done(bot: Bot, msg: Message, name: String, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let mode = current_mode(&stg).await?;
+    let name = deck_name(&name)?;
+
+    let (target, deck, player) = (mode.clone(), name.clone(), player(msg.chat.id, sender(&msg)?));
+    stg.blocking(move |stg| stg.record_done(&deck, &target, None, &player)).await?;
+
+    bot.send_message(msg.chat.id, Text::MarkedDone { mode, deck: name }.render(lang)).await?;
+    Ok(())
+}
+
+/// 커맨드를 보낸 사용자. 채널 메시지처럼 보낸 사람이 없으면 기록할 수 없음
+fn sender(msg: &Message) -> Result<&User, UserError> {
+    msg.from.as_ref().ok_or(UserError::from(Text::UnknownUser))
+}
+
+fn player(chat_id: ChatId, user: &User) -> Player {
+    Player { chat_id: chat_id.0, user_id: user.id.0, name: user.full_name() }
+}
+
+/// 직접 입력한 덱 이름 검증
+fn deck_name(input: &str) -> Result<String, UserError> {
+    valid_name(input).ok_or(UserError::from(Text::InvalidDeckName))
+}
+
+async fn export(bot: Bot, msg: Message, format: String, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let format = ExportFormat::parse(&format)?;
+    // memo. 커맨드를 보낸 사용자의 기록만 내보냄
+    let user_id = sender(&msg)?.id.0;
+
+    let mut records = Vec::new();
+    for mode in [Mode::main, Mode::pbe] {
+        let target = mode.clone();
+        records.extend(stg.blocking(move |stg| stg.retrieve_done_records(&target, Some(user_id))).await?.into_iter().map(|record| ExportRecord {
+            mode: mode.clone(),
+            deck: record.name,
+            created_at: record.created_at,
+            placement: record.placement,
+        }));
+    }
+
+    if records.is_empty() {
+        bot.send_message(msg.chat.id, Text::NothingToExport.render(lang)).await?;
+        return Ok(());
+    }
+
+    let content = transfer::export(&records, format)?;
+    bot.send_document(msg.chat.id, InputFile::memory(content).file_name(format.file_name()))
+       .caption(Text::ExportCaption(records.len()).render(lang))
+       .await?;
+    Ok(())
+}
+
+async fn import(bot: Bot, dialogue: MyDialogue, msg: Message, lang: Lang) -> HandlerResult {
+    bot.send_message(msg.chat.id, Text::AskImportFile.render(lang)).await?;
+    dialogue.update(State::Import).await?;
+    Ok(())
+}
+
+// memo. 파일 전체를 검증한 뒤에 저장하므로 잘못된 줄이 있으면 아무것도 가져오지 않음
+async fn import_file(bot: Bot, dialogue: MyDialogue, msg: Message, stg: Storage, access: Arc<AccessControl>, lang: Lang) -> HandlerResult {
+
+    // memo. 대기 상태는 채팅 단위이므로 /import 를 보낸 관리자가 아닌 멤버가 파일을 보낼 수 있음. 보낸 사용자를 다시 확인
+    if !access.is_admin(msg.from.as_ref().map(|user| user.id)) {
+        log::warn!("import refused. chat: {}, user: {:?}", msg.chat.id, msg.from.as_ref().map(|user| user.id));
+        bot.send_message(msg.chat.id, Text::AdminOnlyImport.render(lang)).await?;
+        return Ok(());
+    }
+
+    let Some(document) = msg.document() else {
+        return Ok(());
+    };
+    if document.file.size > MAX_IMPORT_BYTES {
+        Err(UserError::from(Text::FileTooLarge))?
+    }
+
+    let file = bot.get_file(document.file.id.clone()).await?;
+    let mut content = Vec::new();
+    bot.download_file(&file.path, &mut content)
+       .await
+       .map_err(|e| BotError::Internal(Box::new(e)))?;
+
+    let format = ExportFormat::detect(document.file_name.as_deref(), &content);
+    let records = transfer::parse(&content, format)?;
+
+    // memo. 가져온 기록은 파일을 보낸 사용자의 기록
+    let player = player(msg.chat.id, sender(&msg)?);
+    let mut text = Text::ImportDone.render(lang);
+    for mode in [Mode::main, Mode::pbe] {
+        let done: Vec<DoneRecord> = records.iter()
+            .filter(|record| record.mode == mode)
+            .map(|record| DoneRecord {
+                name: record.deck.clone(),
+                created_at: record.created_at.clone(),
+                placement: record.placement,
+            })
+            .collect();
+        if done.is_empty() {
+            continue;
+        }
+
+        let (target, count, player) = (mode.clone(), done.len(), player.clone());
+        let imported = stg.blocking(move |stg| stg.import_done(&target, &done, Some(&player))).await?;
+        text.push_str(&format!("\n{}", Text::ImportSummary { mode, imported, skipped: count - imported }.render(lang)));
+    }
+
+    bot.send_message(msg.chat.id, text).await?;
+    dialogue.exit().await?;
+    Ok(())
+}
+
+async fn alias(bot: Bot, msg: Message, input: String, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let (old, new) = input.split_once("=>").ok_or(UserError::from(Text::InvalidAlias))?;
+    let (old, new) = (normalize(old), normalize(new));
+
+    let old_key = canonical_key(&old);
+    if old_key.is_empty() || canonical_key(&new).is_empty() {
+        Err(UserError::from(Text::InvalidAlias))?
+    }
+
+    // memo. 별칭이 다른 별칭을 가리키지 않도록 새 이름의 최종 키로 저장
+    let matcher = DeckMatcher::new(stg.blocking(Storage::retrieve_aliases).await?);
+    let target = matcher.key(&new);
+    if target == old_key {
+        Err(UserError::from(Text::AlreadySameDeck))?
+    }
+
+    stg.blocking(move |stg| stg.upsert_alias(&old_key, &target)).await?;
+
+    bot.send_message(msg.chat.id, Text::AliasMerged { old, new }.render(lang)).await?;
+    Ok(())
+}
+
+async fn leaderboard(bot: Bot, msg: Message, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let chat_id = msg.chat.id.0;
+    // memo. 시즌이 바뀌면 완료 이력이 season_record로 옮겨지므로 모드 테이블은 현재 시즌 기록만 가짐
+    let (mode, scores, season) = stg.blocking(move |stg| {
+        let mode = stg.select_mode()?;
+        let scores = stg.leaderboard(chat_id, &mode)?;
+        Ok((mode, scores, stg.current_season()?))
+    }).await?;
+    record_mode(&mode);
+    if scores.is_empty() {
+        bot.send_message(msg.chat.id, Text::EmptyLeaderboard(mode).render(lang)).await?;
+        return Ok(());
+    }
+
+    let season = season.map(|season| season.name);
+    let mut text = Text::LeaderboardTitle { mode, season }.render(lang);
+    for (rank, score) in rank(scores) {
+        text.push_str(&format!("\n{}", Text::LeaderboardRow { rank, name: score.name, done: score.done }.render(lang)));
+    }
+
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+async fn subscribe(bot: Bot, msg: Message, input: String, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let schedule = Schedule::parse(&input).map_err(UserError::from)?;
+    // memo. 요약의 완료 기록은 구독한 사용자 기준
+    let (chat_id, user_id, target) = (msg.chat.id.0, sender(&msg)?.id.0, schedule.clone());
+    stg.blocking(move |stg| stg.upsert_subscription(chat_id, user_id, &target)).await?;
+
+    let text = Text::Subscribed {
+        time: schedule.at.format("%H:%M").to_string(),
+        offset: schedule.offset_label(),
+        weekday: schedule.weekday,
+    };
+    bot.send_message(msg.chat.id, text.render(lang)).await?;
+    Ok(())
+}
+
+async fn unsubscribe(bot: Bot, msg: Message, stg: Storage, lang: Lang) -> HandlerResult {
+
+    let chat_id = msg.chat.id.0;
+    let text = if stg.blocking(move |stg| stg.delete_subscription(chat_id)).await? {
+        Text::Unsubscribed
+    } else {
+        Text::NotSubscribed
+    };
+    bot.send_message(msg.chat.id, text.render(lang)).await?;
+    Ok(())
+}
+
+// memo. BotFather에서 inline mode를 켜야 @봇이름 검색어 로 호출됨
+async fn inline_query(bot: Bot,
+                q: InlineQuery,
+                stg: Storage,
+                crawler: Arc<LolcheggCrawler>,
+                cache: Arc<DeckCache>,
+                lang: Lang) -> HandlerResult
+{
+    let mode = current_mode(&stg).await?;
+
+    let target = mode.clone();
+    let cards = cache.get_or_load(&mode, async move {
+        Ok::<_, BotError>(tokio::task::spawn_blocking(move || crawler.deck_cards(&target)).await??)
+    }).await?;
+
+    let results = cards.iter()
+        .filter(|card| matches_query(&card.name, &q.query))
+        .take(MAX_INLINE_RESULTS)
+        .enumerate()
+        .map(|(i, card)| {
+            let text = Text::DeckCard { name: card.name.clone(), tier: card.tier.clone(), champions: card.champions.clone() };
+            let summary = Text::DeckCardSummary { tier: card.tier.clone(), champions: card.champions.clone() };
+            InlineQueryResult::Article(
+                InlineQueryResultArticle::new(i.to_string(), card.name.clone(), InputMessageContent::Text(InputMessageContentText::new(text.render(lang))))
+                    .description(summary.render(lang))
+            )
+        })
+        .collect::<Vec<InlineQueryResult>>();
+
+    bot.answer_inline_query(q.id, results).cache_time(INLINE_CACHE_MINUTES as u32 * 60).await?;
+    Ok(())
+}
+
+/// 일시적인 오류는 한 번 재시도하고, css path가 깨진 경우 경로를 갱신한 뒤 다시 조회
+pub(super) fn load_deck(crawler: &LolcheggCrawler, mode: &Mode) -> Result<Vec<String>, CrawlError> {
+    match crawler.recommended_deck(mode) {
+        Err(e) if e.is_transient() => {
+            log::warn!("transient crawl error. retry once. {}", e);
+            std::thread::sleep(Duration::from_secs(1));
+            crawler.recommended_deck(mode)
+        }
+        Err(e) if e.is_broken_path() => {
+            log::warn!("css path seems broken. update path and retry. {}", e);
+            crawler.update_css_path()?;
+            crawler.recommended_deck(mode)
+        }
+        result => result,
+    }
+}
+
+pub(super) fn todo_deck (mut recom : Vec<String> , done : Vec<String>, matcher: &DeckMatcher) -> [Vec<String>;2] {
+
+    // memo. 사이트에서 덱 이름이 바뀌어도 완료 기록이 유지되도록 이름 키로 비교
+    let mut done_flags = matcher.done_flags(&recom, &done);
 
     let mut normal = Vec::<String>::new();
     let mut special = Vec::<String>::new();
@@ -295,11 +1211,11 @@ fn todo_deck (mut recom : Vec<String> , done : Vec<String>) -> [Vec<String>;2] {
 
         let target = recom.remove(i);
 
-        if let Some(true) =done_map.get(&target) {
+        if done_flags.remove(i) {
             continue;
         } 
 
-        if target.starts_with("[") {
+        if is_special(&target) {
             special.push(target);
         } else if !is_normal_picked {
             normal.push(target);
@@ -310,6 +1226,18 @@ fn todo_deck (mut recom : Vec<String> , done : Vec<String>) -> [Vec<String>;2] {
     [normal,special]
 }
 
+/// 도전 목록 중 완료하지 않았고 크롤링한 메타와 겹치지 않는 덱
+fn todo_challenge(custom: Vec<String>, meta: &[String], done: &[String], matcher: &DeckMatcher) -> Vec<String> {
+    let meta_keys: Vec<String> = meta.iter().map(|deck| matcher.key(deck)).collect();
+    let done_flags = matcher.done_flags(&custom, done);
+
+    custom.into_iter()
+        .zip(done_flags)
+        .filter(|(deck, done)| !done && !meta_keys.contains(&matcher.key(deck)))
+        .map(|(deck, _)| deck)
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -325,5 +1253,53 @@ mod test {
             Err(_) => print!("Fail")
         }
     }
+
+    #[test]
+    fn command_args_test() {
+        assert!(matches!(Command::parse("/update pbe", "lolche_bot"), Ok(Command::Update(mode)) if mode == "pbe"));
+        assert!(matches!(Command::parse("/done 10", "lolche_bot"), Ok(Command::Done(count)) if count == "10"));
+        assert!(matches!(Command::parse("/switch", "lolche_bot"), Ok(Command::Switch(mode)) if mode.is_empty()));
+        assert!(matches!(Command::parse("/leaderboard@lolche_bot", "lolche_bot"), Ok(Command::Leaderboard)));
+        assert_eq!(Mode::parse(" PBE "), Some(Mode::pbe));
+        assert_eq!(Mode::parse("ranked"), None);
+    }
+
+    #[test]
+    fn update_command_test() {
+        let update = |text: &str| serde_json::from_str::<Update>(&serde_json::json!({
+            "update_id": 1,
+            "message": {
+                "message_id": 1,
+                "date": 1734000000,
+                "chat": { "id": 1, "type": "private", "first_name": "tester" },
+                "from": { "id": 1, "is_bot": false, "first_name": "tester" },
+                "text": text
+            }
+        }).to_string()).unwrap();
+
+        assert_eq!(update_command(&update("/undo_reset@lolche_bot")).as_deref(), Some("undo_reset"));
+        assert_eq!(update_command(&update("/Update pbe")).as_deref(), Some("update"));
+        assert_eq!(update_command(&update("hello")), None);
+    }
+
+    #[test]
+    fn deck_name_test() {
+        assert_eq!(deck_name("  My   deck ").unwrap(), "My deck");
+        assert!(deck_name(&"a".repeat(30)).is_ok());
+        assert!(deck_name(&"a".repeat(31)).is_err());
+        assert!(deck_name(&"덱".repeat(21)).is_ok());
+        assert!(deck_name(&"덱".repeat(22)).is_err());
+        assert!(deck_name("   ").is_err());
+    }
+
+    #[test]
+    fn todo_challenge_test() {
+        let matcher = DeckMatcher::new(std::collections::HashMap::new());
+        let custom = vec![String::from("나만의 덱"), String::from("완료한 덱"), String::from("메타  덱")];
+        let meta = vec![String::from("메타 덱")];
+        let done = vec![String::from("완료한 덱")];
+
+        assert_eq!(todo_challenge(custom, &meta, &done, &matcher), vec![String::from("나만의 덱")]);
+    }
 }
 
@@ -0,0 +1,197 @@
+use std::collections::{HashMap, HashSet};
+
+/// 이름 키가 이 값 이상 비슷하면 이름이 바뀐 덱 후보로 봄
+const FUZZY_THRESHOLD: f64 = 0.85;
+/// 별칭이 순환하더라도 멈추도록 따라가는 최대 횟수
+const MAX_ALIAS_DEPTH: usize = 8;
+/// 완료 테이블의 name 컬럼은 VARCHAR(30)
+pub const MAX_NAME_CHARS: usize = 30;
+/// 버튼 callback data는 64바이트까지만 허용됨
+pub const MAX_NAME_BYTES: usize = 64;
+
+/// 공백을 하나로 줄이고 앞뒤 공백 제거
+pub fn normalize(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 직접 입력한 덱 이름을 정규화. 비어 있거나 저장, 버튼에 쓸 수 없을 만큼 길면 None
+pub fn valid_name(input: &str) -> Option<String> {
+    let name = normalize(input);
+    (!name.is_empty() && name.chars().count() <= MAX_NAME_CHARS && name.len() <= MAX_NAME_BYTES).then_some(name)
+}
+
+/// "[상징] 6자동기계 코그모 리롤덱" -> (Some("상징"), "6자동기계 코그모 리롤덱")
+pub fn split_tag(name: &str) -> (Option<&str>, &str) {
+    let name = name.trim();
+    if let Some(rest) = name.strip_prefix('[') {
+        if let Some((tag, body)) = rest.split_once(']') {
+            return (Some(tag.trim()), body.trim());
+        }
+    }
+    (None, name)
+}
+
+/// 덱 비교에 쓰는 키. 태그, 공백, 이모지, 기호를 빼고 소문자로 맞춤
+pub fn canonical_key(name: &str) -> String {
+    let (_, body) = split_tag(name);
+    body.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 검색어의 키가 덱 이름 키에 포함되면 일치. 빈 검색어는 모두 일치
+pub fn matches_query(name: &str, query: &str) -> bool {
+    canonical_key(name).contains(&canonical_key(query))
+}
+
+/// 0.0 ~ 1.0. 편집 거리 기준
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+/// 숫자가 같고 충분히 비슷하면 이름이 바뀐 같은 덱일 수 있음
+/// 예) 6자동기계 코그모와 4자동기계 코그모는 숫자만 다른 다른 덱
+pub fn is_rename_candidate(a: &str, b: &str) -> bool {
+    let digits = |key: &str| key.chars().filter(char::is_ascii_digit).collect::<String>();
+    digits(a) == digits(b) && similarity(a, b) >= FUZZY_THRESHOLD
+}
+
+/// 별칭 테이블로 완료 기록을 크롤링한 덱 이름에 대응시킴
+pub struct DeckMatcher {
+    aliases: HashMap<String, String>,
+}
+
+impl DeckMatcher {
+
+    /// aliases는 별칭 키 -> 대상 키
+    pub fn new(aliases: HashMap<String, String>) -> Self {
+        Self { aliases }
+    }
+
+    /// 별칭을 따라간 최종 키
+    pub fn key(&self, name: &str) -> String {
+        let mut key = canonical_key(name);
+        for _ in 0..MAX_ALIAS_DEPTH {
+            match self.aliases.get(&key) {
+                Some(next) if *next != key => key = next.clone(),
+                _ => break,
+            }
+        }
+        key
+    }
+
+    /// meta 순서대로 완료 여부. 별칭을 따라간 키가 같은 기록만 대응
+    // memo. 비슷한 이름은 다른 덱일 수 있으므로 완료로 보지 않고 /alias 후보로 로그만 남김. 관리자가 확인 후 병합
+    pub fn done_flags(&self, meta: &[String], done: &[String]) -> Vec<bool> {
+        let meta_keys: Vec<String> = meta.iter().map(|deck| self.key(deck)).collect();
+        let mut remaining: HashSet<String> = done.iter().map(|deck| self.key(deck)).collect();
+
+        let flags: Vec<bool> = meta_keys.iter().map(|key| remaining.contains(key)).collect();
+        for key in meta_keys.iter() {
+            remaining.remove(key);
+        }
+
+        for (i, key) in meta_keys.iter().enumerate() {
+            if flags[i] {
+                continue;
+            }
+            let best = remaining.iter()
+                .filter(|record| is_rename_candidate(key, record))
+                .max_by(|a, b| similarity(key, a).total_cmp(&similarity(key, b)));
+
+            if let Some(record) = best {
+                log::info!("similar deck found. if it was renamed, run /alias {} => {}", record, meta[i]);
+            }
+        }
+        flags
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn canonical_key_test() {
+        assert_eq!(normalize("  6자동기계   코그모\t리롤덱 "), "6자동기계 코그모 리롤덱");
+        assert_eq!(split_tag("[상징] 6자동기계 코그모 리롤덱"), (Some("상징"), "6자동기계 코그모 리롤덱"));
+        assert_eq!(canonical_key("[상징] 6자동기계 코그모 리롤덱"), canonical_key("[특성]  6자동기계코그모 리롤덱 🔥"));
+        assert_eq!(canonical_key("Jinx Reroll"), "jinxreroll");
+    }
+
+    #[test]
+    fn matches_query_test() {
+        assert!(matches_query("[상징] 6자동기계 코그모 리롤덱", "코그모 리롤"));
+        assert!(matches_query("Jinx Reroll", "jinx"));
+        assert!(matches_query("Jinx Reroll", " "));
+        assert!(!matches_query("Jinx Reroll", "vi"));
+    }
+
+    #[test]
+    fn similarity_test() {
+        assert_eq!(similarity("abc", "abc"), 1.0);
+        assert_eq!(similarity("", ""), 1.0);
+        assert!(similarity("6자동기계코그모리롤덱", "6자동기계코그모리롤") >= FUZZY_THRESHOLD);
+        assert!(similarity("코그모", "징크스") < FUZZY_THRESHOLD);
+    }
+
+    #[test]
+    fn done_flags_test() {
+        let mut aliases = HashMap::new();
+        aliases.insert(canonical_key("옛 이름 덱"), canonical_key("새 이름 덱"));
+        let matcher = DeckMatcher::new(aliases);
+
+        let meta = vec![
+            String::from("새 이름 덱"),
+            String::from("[특성] 6자동기계 코그모 리롤덱"),
+            String::from("6자동기계 코그모 리롤"),
+            String::from("징크스"),
+        ];
+        let done = vec![
+            String::from("옛 이름 덱"),
+            String::from("[상징] 6자동기계 코그모 리롤덱"),
+        ];
+
+        assert_eq!(matcher.done_flags(&meta, &done), vec![true, true, false, false]);
+
+        // memo. 비슷한 이름은 별칭으로 병합하기 전까지 완료로 보지 않음
+        let done = vec![String::from("6자동기계 코그모 리롤")];
+        assert_eq!(matcher.done_flags(&meta[1..2], &done), vec![false]);
+    }
+
+    #[test]
+    fn rename_candidate_test() {
+        assert!(is_rename_candidate(&canonical_key("6자동기계 코그모 리롤덱"), &canonical_key("6자동기계 코그모 리롤")));
+        // memo. 숫자만 다른 덱은 0.85 이상 비슷해도 다른 덱
+        assert!(similarity("6자동기계코그모리롤덱", "4자동기계코그모리롤덱") >= FUZZY_THRESHOLD);
+        assert!(!is_rename_candidate("6자동기계코그모리롤덱", "4자동기계코그모리롤덱"));
+        assert!(!is_rename_candidate("코그모", "징크스"));
+    }
+
+    #[test]
+    fn alias_cycle_test() {
+        let mut aliases = HashMap::new();
+        aliases.insert(String::from("a"), String::from("b"));
+        aliases.insert(String::from("b"), String::from("a"));
+
+        let matcher = DeckMatcher::new(aliases);
+        matcher.key("a");
+    }
+}
@@ -0,0 +1,317 @@
+use std::{collections::HashMap, sync::Arc, time::{Duration, Instant}};
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use teloxide::prelude::*;
+
+use crate::{crawl::crawl::LolcheggCrawler, db::db::{Storage, Subscription}};
+
+use super::{bot::{load_deck, todo_deck}, deck::DeckMatcher, error::BotError, i18n::Text, traits::Mode};
+
+/// 구독 채팅의 발송 시각을 확인하는 주기
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+/// 덱 목록을 불러오지 못하면 이 시간 동안은 크롤링하지 않음
+const META_RETRY_SECONDS: u64 = 10 * 60;
+/// 시간대를 지정하지 않으면 KST
+const DEFAULT_OFFSET_MINUTES: i32 = 9 * 60;
+const DEFAULT_TIME: (u32, u32) = (9, 0);
+
+/// 채팅별 요약 발송 시각. weekday가 없으면 매일
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub at: NaiveTime,
+    pub offset_minutes: i32,
+    pub weekday: Option<Weekday>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            at: NaiveTime::from_hms_opt(DEFAULT_TIME.0, DEFAULT_TIME.1, 0).unwrap_or_default(),
+            offset_minutes: DEFAULT_OFFSET_MINUTES,
+            weekday: None,
+        }
+    }
+}
+
+impl Schedule {
+
+    /// "08:30 +09:00 mon" 처럼 시각, UTC 기준 시간대, 요일을 순서 없이 입력. 빠진 값은 기본값
+    pub fn parse(input: &str) -> Result<Self, Text> {
+        let mut schedule = Self::default();
+        for token in input.split_whitespace() {
+            let token = token.to_lowercase();
+            if token == "daily" || token == "매일" {
+                schedule.weekday = None;
+            } else if token.starts_with('+') || token.starts_with('-') || token.starts_with("utc") {
+                schedule.offset_minutes = parse_offset(token.trim_start_matches("utc")).ok_or(Text::InvalidSchedule)?;
+            } else if let Ok(at) = NaiveTime::parse_from_str(&token, "%H:%M") {
+                schedule.at = at;
+            } else {
+                schedule.weekday = Some(parse_weekday(&token).ok_or(Text::InvalidSchedule)?);
+            }
+        }
+        Ok(schedule)
+    }
+
+    pub fn offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.offset_minutes * 60).unwrap_or(FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// 예) +09:00
+    pub fn offset_label(&self) -> String {
+        let minutes = self.offset_minutes.abs();
+        format!("{}{:02}:{:02}", if self.offset_minutes < 0 { '-' } else { '+' }, minutes / 60, minutes % 60)
+    }
+
+    /// 발송 시각이 지났고 그 날짜에 아직 보내지 않았으면 보낼 날짜를 반환
+    pub fn due(&self, now: DateTime<Utc>, last_sent_on: Option<NaiveDate>) -> Option<NaiveDate> {
+        let local = now.with_timezone(&self.offset());
+        let today = local.date_naive();
+
+        if local.time() < self.at || last_sent_on.is_some_and(|sent| sent >= today) {
+            return None;
+        }
+        if self.weekday.is_some_and(|weekday| weekday != today.weekday()) {
+            return None;
+        }
+        Some(today)
+    }
+
+    /// 구독한 시간대에서 그 날짜의 시작과 다음 날짜의 시작
+    pub fn day_range(&self, day: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = |day: NaiveDate| (day.and_time(NaiveTime::MIN) - self.offset()).and_utc();
+        (start(day), start(day.succ_opt().unwrap_or(day)))
+    }
+
+    pub fn minute_of_day(&self) -> u16 {
+        (self.at.hour() * 60 + self.at.minute()) as u16
+    }
+
+    pub fn from_minute_of_day(minute: u16, offset_minutes: i32, weekday: Option<u8>) -> Self {
+        Self {
+            at: NaiveTime::from_hms_opt(minute as u32 / 60 % 24, minute as u32 % 60, 0).unwrap_or_default(),
+            offset_minutes,
+            weekday: weekday.and_then(|day| Weekday::try_from(day).ok()),
+        }
+    }
+}
+
+fn parse_offset(input: &str) -> Option<i32> {
+    let (sign, rest) = match input.chars().next()? {
+        '+' => (1, &input[1..]),
+        '-' => (-1, &input[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let (hours, minutes) = (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?);
+    if hours > 14 || minutes >= 60 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input {
+        "월" | "월요일" => Some(Weekday::Mon),
+        "화" | "화요일" => Some(Weekday::Tue),
+        "수" | "수요일" => Some(Weekday::Wed),
+        "목" | "목요일" => Some(Weekday::Thu),
+        "금" | "금요일" => Some(Weekday::Fri),
+        "토" | "토요일" => Some(Weekday::Sat),
+        "일" | "일요일" => Some(Weekday::Sun),
+        _ => input.parse::<Weekday>().ok(),
+    }
+}
+
+/// 봇이 실행되는 동안 주기적으로 구독 채팅에 요약을 보냄. 한 채팅의 실패가 다른 채팅 발송을 막지 않음
+pub async fn run(bot: Bot, stg: Storage, crawler: Arc<LolcheggCrawler>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+    let mut meta = MetaCache::default();
+    loop {
+        interval.tick().await;
+
+        let subscriptions = match stg.blocking(Storage::retrieve_subscriptions).await {
+            Ok(subscriptions) => subscriptions,
+            Err(e) => {
+                log::warn!("fail to load digest subscriptions. {}", e);
+                continue;
+            }
+        };
+
+        let now = Utc::now();
+        let due: Vec<(Subscription, NaiveDate)> = subscriptions.into_iter()
+            .filter_map(|sub| sub.schedule.due(now, sub.last_sent_on).map(|day| (sub, day)))
+            .collect();
+        if due.is_empty() {
+            continue;
+        }
+
+        // memo. 같은 주기에 보내는 채팅끼리는 크롤링 결과를 공유
+        meta.clear();
+        for (sub, day) in due {
+            if let Err(e) = send_digest(&bot, &stg, &crawler, &mut meta, &sub, day).await {
+                log::warn!("fail to send digest. chat: {}, error: {}", sub.chat_id, e);
+            }
+        }
+    }
+}
+
+/// 발송 주기 동안 모드별 덱 목록을 공유하고, 불러오지 못하면 한동안 다시 크롤링하지 않음
+#[derive(Default)]
+struct MetaCache {
+    decks: HashMap<Mode, Option<Vec<String>>>,
+    failed_at: HashMap<Mode, Instant>,
+}
+
+impl MetaCache {
+
+    /// 주기마다 새로 크롤링. 실패 시각은 유지
+    fn clear(&mut self) {
+        self.decks.clear();
+    }
+
+    fn backing_off(&self, mode: &Mode, now: Instant) -> bool {
+        self.failed_at.get(mode)
+            .is_some_and(|failed_at| now.duration_since(*failed_at) < Duration::from_secs(META_RETRY_SECONDS))
+    }
+
+    /// 덱 목록. 이번 주기에 이미 실패했거나 재시도 대기 중이면 None
+    async fn load(&mut self, crawler: &Arc<LolcheggCrawler>, mode: &Mode) -> Option<Vec<String>> {
+        if let Some(decks) = self.decks.get(mode) {
+            return decks.clone();
+        }
+        if self.backing_off(mode, Instant::now()) {
+            return None;
+        }
+
+        let (loader, target) = (crawler.clone(), mode.clone());
+        let loaded = tokio::task::spawn_blocking(move || load_deck(&loader, &target)).await
+            .map_err(BotError::from)
+            .and_then(|decks| decks.map_err(BotError::from));
+        let decks = match loaded {
+            Ok(decks) => {
+                self.failed_at.remove(mode);
+                Some(decks)
+            }
+            Err(e) => {
+                log::warn!("fail to load decks for digest. retry after {} seconds. {}", META_RETRY_SECONDS, e);
+                self.failed_at.insert(mode.clone(), Instant::now());
+                None
+            }
+        };
+        self.decks.insert(mode.clone(), decks.clone());
+        decks
+    }
+}
+
+async fn send_digest(bot: &Bot,
+                stg: &Storage,
+                crawler: &Arc<LolcheggCrawler>,
+                meta: &mut MetaCache,
+                sub: &Subscription,
+                day: NaiveDate) -> Result<(), BotError>
+{
+    let chat_id = sub.chat_id;
+    let (mode, lang) = stg.blocking(move |stg| Ok((stg.select_mode()?, stg.select_lang(chat_id)?))).await?;
+
+    let recommended = meta.load(crawler, &mode).await;
+
+    // memo. created_at은 DB 서버 시간대이므로 구독한 시간대의 어제를 UTC 구간으로 바꿔서 조회
+    let (from, to) = sub.schedule.day_range(day.pred_opt().unwrap_or(day));
+    let (target, user_id) = (mode.clone(), sub.user_id);
+    let (done, aliases, yesterday) = stg.blocking(move |stg| {
+        Ok((stg.retrieve_done(&target, user_id)?, stg.retrieve_aliases()?, stg.retrieve_done_between(&target, user_id, from, to)?))
+    }).await?;
+
+    // memo. 덱 목록이 없어도 어제 완료한 덱은 보여줄 수 있으므로 그대로 발송
+    let text = match recommended {
+        Some(recommended) => {
+            let matcher = DeckMatcher::new(aliases);
+            let [normal, special] = todo_deck(recommended, done, &matcher);
+            Text::Digest { mode, normal: normal.into_iter().next(), special, yesterday }
+        }
+        None => Text::DigestWithoutDecks { mode, yesterday },
+    };
+    bot.send_message(ChatId(sub.chat_id), text.render(lang)).await?;
+
+    // memo. 발송에 성공한 경우에만 기록하여 실패하면 다음 주기에 다시 시도
+    stg.blocking(move |stg| stg.mark_digest_sent(chat_id, day)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(Schedule::parse("").unwrap(), Schedule::default());
+
+        let schedule = Schedule::parse("mon 07:30 -05:00").unwrap();
+        assert_eq!(schedule.at, NaiveTime::from_hms_opt(7, 30, 0).unwrap());
+        assert_eq!(schedule.offset_minutes, -300);
+        assert_eq!(schedule.weekday, Some(Weekday::Mon));
+        assert_eq!(schedule.offset_label(), "-05:00");
+
+        assert_eq!(Schedule::parse("금요일").unwrap().weekday, Some(Weekday::Fri));
+        assert_eq!(Schedule::parse("utc+5:30").unwrap().offset_minutes, 330);
+        assert!(Schedule::parse("25:00").is_err());
+        assert!(Schedule::parse("+15:00").is_err());
+    }
+
+    #[test]
+    fn due_test() {
+        let schedule = Schedule::default();
+        // 2024-12-20 09:10 KST
+        let now = Utc.with_ymd_and_hms(2024, 12, 20, 0, 10, 0).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 12, 20).unwrap();
+
+        assert_eq!(schedule.due(now, None), Some(today));
+        assert_eq!(schedule.due(now, today.pred_opt()), Some(today));
+        assert_eq!(schedule.due(now, Some(today)), None);
+        // 2024-12-20 08:50 KST
+        assert_eq!(schedule.due(Utc.with_ymd_and_hms(2024, 12, 19, 23, 50, 0).unwrap(), None), None);
+
+        let weekly = Schedule { weekday: Some(Weekday::Mon), ..Schedule::default() };
+        assert_eq!(weekly.due(now, None), None);
+    }
+
+    #[test]
+    fn minute_of_day_test() {
+        let schedule = Schedule::parse("sun 21:45 +01:00").unwrap();
+        let restored = Schedule::from_minute_of_day(schedule.minute_of_day(), 60, Some(Weekday::Sun.num_days_from_monday() as u8));
+        assert_eq!(restored, schedule);
+    }
+
+    #[test]
+    fn day_range_test() {
+        let day = NaiveDate::from_ymd_opt(2024, 12, 19).unwrap();
+        // 2024-12-19 00:00 KST ~ 2024-12-20 00:00 KST
+        assert_eq!(Schedule::default().day_range(day), (
+            Utc.with_ymd_and_hms(2024, 12, 18, 15, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 12, 19, 15, 0, 0).unwrap(),
+        ));
+
+        let schedule = Schedule::parse("-05:00").unwrap();
+        assert_eq!(schedule.day_range(day).0, Utc.with_ymd_and_hms(2024, 12, 19, 5, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn meta_backoff_test() {
+        let mut meta = MetaCache::default();
+        let now = Instant::now();
+        assert!(!meta.backing_off(&Mode::main, now));
+
+        meta.failed_at.insert(Mode::main, now);
+        assert!(meta.backing_off(&Mode::main, now + Duration::from_secs(60)));
+        assert!(!meta.backing_off(&Mode::pbe, now));
+        assert!(!meta.backing_off(&Mode::main, now + Duration::from_secs(META_RETRY_SECONDS)));
+
+        // memo. 다음 주기로 넘어가도 실패 시각은 유지
+        meta.decks.insert(Mode::main, None);
+        meta.clear();
+        assert!(meta.decks.is_empty());
+        assert!(meta.backing_off(&Mode::main, now));
+    }
+}
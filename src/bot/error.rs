@@ -0,0 +1,132 @@
+use crate::{crawl::error::CrawlError, db::error::StorageError};
+
+use super::i18n::{Lang, Text};
+
+/// 사용자의 입력이나 조작이 잘못된 경우의 오류
+#[derive(Debug)]
+pub struct UserError(Text);
+
+impl std::fmt::Display for UserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.render(Lang::default()))
+    }
+}
+
+impl std::error::Error for UserError {}
+
+impl From<Text> for UserError {
+    fn from(text: Text) -> Self {
+        Self(text)
+    }
+}
+
+/// 핸들러에서 발생하는 오류. 종류에 따라 재시도, 자동 수정, 사용자 안내로 대응
+#[derive(Debug)]
+pub enum BotError {
+    User(UserError),
+    Crawl(CrawlError),
+    Storage(StorageError),
+    Telegram(teloxide::RequestError),
+    Internal(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl BotError {
+    /// 오류가 발생한 채팅에 보낼 메시지
+    pub fn msg(&self, lang: Lang) -> String {
+        let text = match self {
+            BotError::User(e) => return e.0.render(lang),
+            BotError::Crawl(CrawlError::Network(_)) => Text::CrawlNetwork,
+            BotError::Crawl(CrawlError::HttpStatus(status)) => Text::CrawlStatus(status.to_string()),
+            BotError::Crawl(_) => Text::CrawlBroken,
+            BotError::Storage(StorageError::Connection(_)) => Text::StorageConnection,
+            BotError::Storage(_) => Text::StorageFailed,
+            BotError::Telegram(_) | BotError::Internal(_) => Text::Unknown,
+        };
+        text.render(lang)
+    }
+}
+
+impl std::fmt::Display for BotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BotError::User(e) => write!(f, "user error. {}", e),
+            BotError::Crawl(e) => write!(f, "crawl error. {}", e),
+            BotError::Storage(e) => write!(f, "storage error. {}", e),
+            BotError::Telegram(e) => write!(f, "telegram error. {}", e),
+            BotError::Internal(e) => write!(f, "internal error. {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BotError::User(e) => Some(e),
+            BotError::Crawl(e) => Some(e),
+            BotError::Storage(e) => Some(e),
+            BotError::Telegram(e) => Some(e),
+            BotError::Internal(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+impl From<UserError> for BotError {
+    fn from(e: UserError) -> Self {
+        Self::User(e)
+    }
+}
+
+impl From<CrawlError> for BotError {
+    fn from(e: CrawlError) -> Self {
+        Self::Crawl(e)
+    }
+}
+
+impl From<StorageError> for BotError {
+    fn from(e: StorageError) -> Self {
+        Self::Storage(e)
+    }
+}
+
+impl From<teloxide::RequestError> for BotError {
+    fn from(e: teloxide::RequestError) -> Self {
+        Self::Telegram(e)
+    }
+}
+
+impl From<tokio::task::JoinError> for BotError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        Self::Internal(Box::new(e))
+    }
+}
+
+// memo. dialogue 저장소 오류는 ErasedStorage를 거치며 Box로 감싸지므로 다시 꺼냄
+impl From<Box<dyn std::error::Error + Send + Sync>> for BotError {
+    fn from(e: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        match e.downcast::<StorageError>() {
+            Ok(e) => Self::Storage(*e),
+            Err(e) => Self::Internal(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn erased_storage_error_test() {
+        let erased: Box<dyn std::error::Error + Send + Sync> = Box::new(StorageError::Serde(serde_json::from_str::<u8>("x").unwrap_err()));
+        assert!(matches!(BotError::from(erased), BotError::Storage(StorageError::Serde(_))));
+
+        let other: Box<dyn std::error::Error + Send + Sync> = "other".into();
+        assert!(matches!(BotError::from(other), BotError::Internal(_)));
+    }
+
+    #[test]
+    fn crawl_msg_test() {
+        assert_eq!(BotError::from(CrawlError::EmptyResult).msg(Lang::Ko), "덱 목록을 찾지 못했습니다. /fix 로 경로를 갱신해 주세요");
+        assert_eq!(BotError::from(CrawlError::EmptyResult).msg(Lang::En), "Cannot find the deck list. Update the path with /fix");
+        assert_eq!(BotError::from(UserError::from(Text::InvalidCommand)).msg(Lang::Ko), "잘못된 커맨드");
+    }
+}
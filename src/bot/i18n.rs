@@ -0,0 +1,524 @@
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+
+use super::traits::Mode;
+
+/// 채팅별 메시지 언어
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Lang {
+    #[default]
+    Ko,
+    En,
+}
+
+impl Lang {
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::Ko => "ko",
+            Lang::En => "en",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "ko" | "kr" | "한국어" => Some(Lang::Ko),
+            "en" | "english" => Some(Lang::En),
+            _ => None,
+        }
+    }
+}
+
+/// 사용자에게 보내는 메시지. 언어별 문구는 ko, en 에서 관리
+#[derive(Debug, Clone, PartialEq)]
+pub enum Text {
+    ModeName(Mode),
+    CurrentMode(Mode),
+    ModeSwitched(Mode),
+    InvalidMode,
+    InvalidDoneCount,
+
+    NextNormalDeck,
+    RemainingSpecialDeck,
+    RemainingChallenge,
+
+    NothingToReset(Mode),
+    ResetConfirm { mode: Mode, count: usize, minutes: u32 },
+    ResetButton(usize),
+    Cancel,
+    AdminOnlyReset,
+    AdminOnlyImport,
+    ResetCanceled,
+    ResetDone { mode: Mode, count: usize, minutes: u32 },
+    NothingToUndo { mode: Mode, minutes: u32 },
+    UndoDone { mode: Mode, count: usize },
+
+    DoneList,
+    PathFixed,
+    RolledBack(String),
+
+    InvalidSeasonName,
+    PreviousSeason,
+    SeasonStarted { previous: String, archived: usize, name: String },
+    CurrentSeason { name: String, started_at: String },
+    NoCurrentSeason,
+    NoPastSeason,
+    PastSeasons,
+    SeasonNotFound,
+    SeasonTitle(String),
+    ModeCount { mode: Mode, count: usize },
+
+    Unauthorized,
+    Forbidden,
+    InvalidCommand,
+
+    DeckNotFound,
+    PlacementButton(u8),
+    AskPlacement(String),
+    SkipPlacement,
+    Completed(String),
+    InvalidPlacement,
+    AskCompletion { deck: String, placement: u8 },
+    Complete,
+    Incomplete,
+    CompletedWithPlacement { deck: String, placement: u8 },
+    GameRecorded { deck: String, placement: u8 },
+
+    NoGames(Mode),
+    PlacementTitle(Mode),
+    GamesToComplete(usize),
+    PlacementSummary { average: f64, top4_rate: f64, games: usize },
+
+    StatsTitle,
+    LoadFailed,
+    ModeStats { total: usize, this_week: usize, meta_progress: String, normal: usize, special: usize, streak: usize },
+
+    ProgressTitle(Mode),
+    NormalDecks,
+    SpecialDecks,
+    PreviousPage,
+    NextPage,
+    PageNotFound,
+
+    EmptyChallenge(Mode),
+    ChallengeList(Mode),
+    ChallengeAdded { mode: Mode, deck: String },
+    ChallengeExists { mode: Mode, deck: String },
+    ChallengeRemoved { mode: Mode, deck: String },
+    ChallengeMissing { mode: Mode, deck: String },
+    MarkedDone { mode: Mode, deck: String },
+    InvalidDeckName,
+
+    InvalidAlias,
+    AlreadySameDeck,
+    AliasMerged { old: String, new: String },
+
+    InvalidExportFormat,
+    NothingToExport,
+    ExportCaption(usize),
+    AskImportFile,
+    FileTooLarge,
+    ImportDone,
+    ImportSummary { mode: Mode, imported: usize, skipped: usize },
+    InvalidJson(String),
+    InvalidCsvLine { line: usize, reason: String },
+    NothingToImport,
+    InvalidRecord { index: usize, reason: Box<Text> },
+    InvalidRecordDeck,
+    InvalidRecordTimestamp,
+    InvalidRecordPlacement,
+
+    DeckCard { name: String, tier: Option<String>, champions: Vec<String> },
+    DeckCardSummary { tier: Option<String>, champions: Vec<String> },
+
+    CurrentLang(Lang),
+    LangChanged(Lang),
+    InvalidLang,
+
+    UnknownUser,
+    LeaderboardTitle { mode: Mode, season: Option<String> },
+    LeaderboardRow { rank: usize, name: String, done: usize },
+    EmptyLeaderboard(Mode),
+
+    InvalidSchedule,
+    Subscribed { time: String, offset: String, weekday: Option<Weekday> },
+    Unsubscribed,
+    NotSubscribed,
+    Digest { mode: Mode, normal: Option<String>, special: Vec<String>, yesterday: Vec<String> },
+    DigestWithoutDecks { mode: Mode, yesterday: Vec<String> },
+
+    PatchReleased(String),
+    PbeStarted,
+    PbeEnded,
+    AutoSwitched(Mode),
+
+    CrawlNetwork,
+    CrawlStatus(String),
+    CrawlBroken,
+    StorageConnection,
+    StorageFailed,
+    Unknown,
+}
+
+impl Text {
+
+    pub fn render(&self, lang: Lang) -> String {
+        match lang {
+            Lang::Ko => self.ko(),
+            Lang::En => self.en(),
+        }
+    }
+
+    fn ko(&self) -> String {
+        let lang = Lang::Ko;
+        match self {
+            Text::ModeName(Mode::main) => String::from("정규 모드"),
+            Text::ModeName(Mode::pbe) => String::from("pbe 모드"),
+            Text::CurrentMode(mode) => format!("현재 모드 : {}", mode.msg(lang)),
+            Text::ModeSwitched(mode) => format!("모드 변경 성공. 현재 모드 : {}", mode.msg(lang)),
+            Text::InvalidMode => String::from("모드는 main 또는 pbe 입니다. 예) /switch pbe"),
+            Text::InvalidDoneCount => String::from("개수는 1 이상의 숫자로 입력해 주세요. 예) /done 10"),
+
+            Text::NextNormalDeck => String::from("다음 일반 덱"),
+            Text::RemainingSpecialDeck => String::from("잔여 특수 덱"),
+            Text::RemainingChallenge => String::from("잔여 도전 목록"),
+
+            Text::NothingToReset(mode) => format!("모드 {}에 삭제할 이력이 없습니다", mode.msg(lang)),
+            Text::ResetConfirm { mode, count, minutes } => format!("모드 {}의 완료 이력 {}건을 삭제할까요?\n삭제 후 {}분 안에 /undo_reset 으로 되돌릴 수 있습니다", mode.msg(lang), count, minutes),
+            Text::ResetButton(count) => format!("삭제 ({}건)", count),
+            Text::Cancel => String::from("취소"),
+            Text::AdminOnlyReset => String::from("죄송합니다. 관리자만 초기화를 확정할 수 있습니다"),
+            Text::AdminOnlyImport => String::from("죄송합니다. 관리자만 기록 파일을 가져올 수 있습니다"),
+            Text::ResetCanceled => String::from("초기화 취소"),
+            Text::ResetDone { mode, count, minutes } => format!("모드 {}에 대한 이력 {}건 삭제 완료. {}분 안에 /undo_reset 으로 되돌릴 수 있습니다", mode.msg(lang), count, minutes),
+            Text::NothingToUndo { mode, minutes } => format!("모드 {}에 {}분 안에 초기화된 이력이 없습니다", mode.msg(lang), minutes),
+            Text::UndoDone { mode, count } => format!("모드 {}에 대한 이력 {}건 복구 완료", mode.msg(lang), count),
+
+            Text::DoneList => String::from("완료 내역"),
+            Text::PathFixed => String::from("css path 수정 완료"),
+            Text::RolledBack(deck) => format!("{} 롤백 완료", deck),
+
+            Text::InvalidSeasonName => String::from("시즌 이름을 30자 이내로 입력해 주세요. 예) /newseason 시즌14"),
+            Text::PreviousSeason => String::from("이전"),
+            Text::SeasonStarted { previous, archived, name } => format!("{} 시즌 종료. 완료 이력 {}건 보관\n새 시즌 {} 시작", previous, archived, name),
+            Text::CurrentSeason { name, started_at } => format!("현재 시즌 : {} ({} ~)", name, started_at),
+            Text::NoCurrentSeason => String::from("현재 시즌 : 없음"),
+            Text::NoPastSeason => String::from("종료된 시즌이 없습니다"),
+            Text::PastSeasons => String::from("지난 시즌"),
+            Text::SeasonNotFound => String::from("선택한 시즌 정보를 찾을 수 없습니다"),
+            Text::SeasonTitle(name) => format!("{} 시즌", name),
+            Text::ModeCount { mode, count } => format!("[{}] {}개", mode.msg(lang), count),
+
+            Text::Unauthorized => String::from("죄송합니다. 이 봇을 사용할 수 있는 권한이 없습니다"),
+            Text::Forbidden => String::from("죄송합니다. 관리자만 사용할 수 있는 커맨드입니다"),
+            Text::InvalidCommand => String::from("잘못된 커맨드"),
+
+            Text::DeckNotFound => String::from("선택한 덱 정보를 찾을 수 없습니다"),
+            Text::PlacementButton(placement) => format!("{}등", placement),
+            Text::AskPlacement(deck) => format!("{} 몇 등 했나요?", deck),
+            Text::SkipPlacement => String::from("등수 없이 완료"),
+            Text::Completed(deck) => format!("{} 완료!", deck),
+            Text::InvalidPlacement => String::from("등수는 1등부터 8등까지 선택할 수 있습니다"),
+            Text::AskCompletion { deck, placement } => format!("{} {}등. 완료로 기록할까요?", deck, placement),
+            Text::Complete => String::from("완료"),
+            Text::Incomplete => String::from("미완료"),
+            Text::CompletedWithPlacement { deck, placement } => format!("{} 완료! ({}등)", deck, placement),
+            Text::GameRecorded { deck, placement } => format!("{} {}등 기록. 다음 판에 다시 도전!", deck, placement),
+
+            Text::NoGames(mode) => format!("모드 {}에 기록된 판이 없습니다", mode.msg(lang)),
+            Text::PlacementTitle(mode) => format!("[{}] 덱별 등수", mode.msg(lang)),
+            Text::GamesToComplete(games) => format!("완료까지 {}판", games),
+            Text::PlacementSummary { average, top4_rate, games } => format!("평균 {:.1}등 · top4 {:.0}% · {}판", average, top4_rate * 100.0, games),
+
+            Text::StatsTitle => String::from("완료 통계"),
+            Text::LoadFailed => String::from("조회 실패"),
+            Text::ModeStats { total, this_week, meta_progress, normal, special, streak } => format!("총 완료 : {}개 (이번 주 {}개)\n메타 달성률 : {}\n일반 / 특수 : {} / {}\n최장 연속 기록 : {}일", total, this_week, meta_progress, normal, special, streak),
+
+            Text::ProgressTitle(mode) => format!("[{}] 진행도", mode.msg(lang)),
+            Text::NormalDecks => String::from("일반 덱"),
+            Text::SpecialDecks => String::from("특수 덱"),
+            Text::PreviousPage => String::from("◀ 이전"),
+            Text::NextPage => String::from("다음 ▶"),
+            Text::PageNotFound => String::from("페이지를 넘길 메시지를 찾을 수 없습니다"),
+
+            Text::EmptyChallenge(mode) => format!("[{}] 도전 목록이 비어 있습니다. 예) /challenge 나만의 덱", mode.msg(lang)),
+            Text::ChallengeList(mode) => format!("[{}] 도전 목록", mode.msg(lang)),
+            Text::ChallengeAdded { mode, deck } => format!("[{}] 도전 목록에 {} 추가", mode.msg(lang), deck),
+            Text::ChallengeExists { mode, deck } => format!("[{}] 도전 목록에 이미 {} 덱이 있습니다", mode.msg(lang), deck),
+            Text::ChallengeRemoved { mode, deck } => format!("[{}] 도전 목록에서 {} 제거", mode.msg(lang), deck),
+            Text::ChallengeMissing { mode, deck } => format!("[{}] 도전 목록에 {} 덱이 없습니다", mode.msg(lang), deck),
+            Text::MarkedDone { mode, deck } => format!("[{}] {} 완료 기록", mode.msg(lang), deck),
+            Text::InvalidDeckName => String::from("덱 이름을 30자, 64바이트 이내로 입력해 주세요. 한글은 21자까지 가능합니다. 예) /markdone 나만의 덱"),
+
+            Text::InvalidAlias => String::from("옛 이름과 새 이름을 => 로 구분해 주세요. 예) /alias 옛 이름 => 새 이름"),
+            Text::AlreadySameDeck => String::from("이미 같은 덱으로 취급되는 이름입니다"),
+            Text::AliasMerged { old, new } => format!("{} 기록을 {} 덱으로 병합했습니다", old, new),
+
+            Text::InvalidExportFormat => String::from("형식은 csv 또는 json만 지원합니다. 예) /export json"),
+            Text::NothingToExport => String::from("내보낼 완료 이력이 없습니다"),
+            Text::ExportCaption(count) => format!("완료 이력 {}건", count),
+            Text::AskImportFile => String::from("가져올 csv 또는 json 파일을 보내 주세요. /export 로 받은 파일과 같은 형식이어야 합니다"),
+            Text::FileTooLarge => String::from("파일은 1MB 이하만 가져올 수 있습니다"),
+            Text::ImportDone => String::from("가져오기 완료"),
+            Text::ImportSummary { mode, imported, skipped } => format!("[{}] {}건 추가, {}건은 이미 있어 건너뜀", mode.msg(lang), imported, skipped),
+            Text::InvalidJson(reason) => format!("json 파일을 읽지 못했습니다. {}", reason),
+            Text::InvalidCsvLine { line, reason } => format!("{}번째 줄을 읽지 못했습니다. {}", line, reason),
+            Text::NothingToImport => String::from("가져올 기록이 없습니다"),
+            Text::InvalidRecord { index, reason } => format!("{}번째 기록 오류. {}", index, reason.ko()),
+            Text::InvalidRecordDeck => String::from("덱 이름은 1~30자, 64바이트 이내여야 합니다. 한글은 21자까지 가능합니다"),
+            Text::InvalidRecordTimestamp => String::from("created_at은 YYYY-MM-DD HH:MM:SS 형식이어야 합니다"),
+            Text::InvalidRecordPlacement => String::from("등수는 1~8 사이여야 합니다"),
+
+            Text::DeckCard { name, tier, champions } => format!("{}\n티어 : {}\n구성 : {}", name, tier.as_deref().unwrap_or("-"), join_or_dash(champions)),
+            Text::DeckCardSummary { tier, champions } => format!("티어 {} · {}", tier.as_deref().unwrap_or("-"), join_or_dash(champions)),
+
+            Text::CurrentLang(lang) => format!("현재 언어 : {}. 예) /lang en", lang.code()),
+            Text::LangChanged(_) => String::from("언어를 한국어로 변경했습니다"),
+            Text::InvalidLang => String::from("지원하는 언어는 ko, en 입니다. 예) /lang en"),
+
+            Text::UnknownUser => String::from("기록할 사용자를 알 수 없습니다"),
+            Text::LeaderboardTitle { mode, season } => format!("[{}] {} 순위", mode.msg(lang), season.as_deref().map(|name| format!("{} 시즌", name)).unwrap_or(String::from("현재 시즌"))),
+            Text::LeaderboardRow { rank, name, done } => format!("{}. {} — {}개", rank, name, done),
+            Text::EmptyLeaderboard(mode) => format!("[{}] 이 채팅에서 완료한 기록이 없습니다", mode.msg(lang)),
+
+            Text::InvalidSchedule => String::from("시각, 시간대, 요일을 확인해 주세요. 예) /subscribe 09:00 +09:00 또는 /subscribe mon 21:00"),
+            Text::Subscribed { time, offset, weekday } => format!("{} {} (UTC{})에 요약을 보내드립니다. 해지는 /unsubscribe", weekday.map(weekday_ko).unwrap_or("매일"), time, offset),
+            Text::Unsubscribed => String::from("요약 구독을 해지했습니다"),
+            Text::NotSubscribed => String::from("구독 중인 요약이 없습니다"),
+            Text::PatchReleased(version) => format!("새 패치 {} 메타가 올라왔습니다. /update 로 확인해 보세요", version),
+            Text::PbeStarted => String::from("pbe 메타가 올라오기 시작했습니다. /update pbe 로 확인해 보세요"),
+            Text::PbeEnded => String::from("pbe 메타가 내려갔습니다"),
+            Text::AutoSwitched(mode) => format!("모드를 {}로 자동 전환했습니다", mode.msg(lang)),
+            Text::Digest { mode, normal, special, yesterday } => format!("[{}] 오늘의 요약\n다음 일반 덱 : {}\n잔여 특수 덱 : {}\n어제 완료 : {}", mode.msg(lang), normal.as_deref().unwrap_or("-"), join_or_dash(special), join_or_dash(yesterday)),
+            Text::DigestWithoutDecks { mode, yesterday } => format!("[{}] 오늘의 요약\n덱 목록을 불러오지 못해 남은 덱은 생략합니다\n어제 완료 : {}", mode.msg(lang), join_or_dash(yesterday)),
+
+            Text::CrawlNetwork => String::from("lolchess.gg에 접속하지 못했습니다. 잠시 후 다시 시도해 주세요"),
+            Text::CrawlStatus(status) => format!("lolchess.gg 응답 오류 ({}). 잠시 후 다시 시도해 주세요", status),
+            Text::CrawlBroken => String::from("덱 목록을 찾지 못했습니다. /fix 로 경로를 갱신해 주세요"),
+            Text::StorageConnection => String::from("기록 저장소에 연결하지 못했습니다. 잠시 후 다시 시도해 주세요"),
+            Text::StorageFailed => String::from("기록 저장소 처리 중 오류가 발생했습니다"),
+            Text::Unknown => String::from("알 수 없는 오류가 발생했습니다"),
+        }
+    }
+
+    fn en(&self) -> String {
+        let lang = Lang::En;
+        match self {
+            Text::ModeName(Mode::main) => String::from("main mode"),
+            Text::ModeName(Mode::pbe) => String::from("pbe mode"),
+            Text::CurrentMode(mode) => format!("Current mode : {}", mode.msg(lang)),
+            Text::ModeSwitched(mode) => format!("Mode switched. Current mode : {}", mode.msg(lang)),
+            Text::InvalidMode => String::from("Mode must be main or pbe. e.g. /switch pbe"),
+            Text::InvalidDoneCount => String::from("Enter a number of 1 or more. e.g. /done 10"),
+
+            Text::NextNormalDeck => String::from("Next normal deck"),
+            Text::RemainingSpecialDeck => String::from("Remaining special decks"),
+            Text::RemainingChallenge => String::from("Remaining challenge list"),
+
+            Text::NothingToReset(mode) => format!("No records to delete in {}", mode.msg(lang)),
+            Text::ResetConfirm { mode, count, minutes } => format!("Delete {} completion records of {}?\nYou can restore them with /undo_reset within {} minutes", count, mode.msg(lang), minutes),
+            Text::ResetButton(count) => format!("Delete ({})", count),
+            Text::Cancel => String::from("Cancel"),
+            Text::AdminOnlyReset => String::from("Sorry. Only admins can confirm a reset"),
+            Text::AdminOnlyImport => String::from("Sorry. Only admins can import a history file"),
+            Text::ResetCanceled => String::from("Reset canceled"),
+            Text::ResetDone { mode, count, minutes } => format!("Deleted {} records of {}. You can restore them with /undo_reset within {} minutes", count, mode.msg(lang), minutes),
+            Text::NothingToUndo { mode, minutes } => format!("No records of {} were reset in the last {} minutes", mode.msg(lang), minutes),
+            Text::UndoDone { mode, count } => format!("Restored {} records of {}", count, mode.msg(lang)),
+
+            Text::DoneList => String::from("Completed decks"),
+            Text::PathFixed => String::from("css path updated"),
+            Text::RolledBack(deck) => format!("{} rolled back", deck),
+
+            Text::InvalidSeasonName => String::from("Enter a season name of up to 30 characters. e.g. /newseason Set14"),
+            Text::PreviousSeason => String::from("Previous"),
+            Text::SeasonStarted { previous, archived, name } => format!("Season {} closed. {} records archived\nSeason {} started", previous, archived, name),
+            Text::CurrentSeason { name, started_at } => format!("Current season : {} ({} ~)", name, started_at),
+            Text::NoCurrentSeason => String::from("Current season : none"),
+            Text::NoPastSeason => String::from("No closed seasons"),
+            Text::PastSeasons => String::from("Past seasons"),
+            Text::SeasonNotFound => String::from("Cannot find the selected season"),
+            Text::SeasonTitle(name) => format!("Season {}", name),
+            Text::ModeCount { mode, count } => format!("[{}] {}", mode.msg(lang), count),
+
+            Text::Unauthorized => String::from("Sorry. You are not allowed to use this bot"),
+            Text::Forbidden => String::from("Sorry. This command is for admins only"),
+            Text::InvalidCommand => String::from("Invalid command"),
+
+            Text::DeckNotFound => String::from("Cannot find the selected deck"),
+            Text::PlacementButton(placement) => format!("#{}", placement),
+            Text::AskPlacement(deck) => format!("What place did you get with {}?", deck),
+            Text::SkipPlacement => String::from("Complete without placement"),
+            Text::Completed(deck) => format!("{} completed!", deck),
+            Text::InvalidPlacement => String::from("Placement must be between 1 and 8"),
+            Text::AskCompletion { deck, placement } => format!("{} #{}. Record it as completed?", deck, placement),
+            Text::Complete => String::from("Complete"),
+            Text::Incomplete => String::from("Incomplete"),
+            Text::CompletedWithPlacement { deck, placement } => format!("{} completed! (#{})", deck, placement),
+            Text::GameRecorded { deck, placement } => format!("{} #{} recorded. Try again next game!", deck, placement),
+
+            Text::NoGames(mode) => format!("No games recorded in {}", mode.msg(lang)),
+            Text::PlacementTitle(mode) => format!("[{}] Placements per deck", mode.msg(lang)),
+            Text::GamesToComplete(games) => format!("completed in {} games", games),
+            Text::PlacementSummary { average, top4_rate, games } => format!("avg #{:.1} · top4 {:.0}% · {} games", average, top4_rate * 100.0, games),
+
+            Text::StatsTitle => String::from("Completion statistics"),
+            Text::LoadFailed => String::from("unavailable"),
+            Text::ModeStats { total, this_week, meta_progress, normal, special, streak } => format!("Total : {} ({} this week)\nMeta progress : {}\nNormal / special : {} / {}\nLongest streak : {} days", total, this_week, meta_progress, normal, special, streak),
+
+            Text::ProgressTitle(mode) => format!("[{}] Progress", mode.msg(lang)),
+            Text::NormalDecks => String::from("Normal decks"),
+            Text::SpecialDecks => String::from("Special decks"),
+            Text::PreviousPage => String::from("◀ Prev"),
+            Text::NextPage => String::from("Next ▶"),
+            Text::PageNotFound => String::from("Cannot find the message to turn the page"),
+
+            Text::EmptyChallenge(mode) => format!("[{}] The challenge list is empty. e.g. /challenge My deck", mode.msg(lang)),
+            Text::ChallengeList(mode) => format!("[{}] Challenge list", mode.msg(lang)),
+            Text::ChallengeAdded { mode, deck } => format!("[{}] {} added to the challenge list", mode.msg(lang), deck),
+            Text::ChallengeExists { mode, deck } => format!("[{}] {} is already in the challenge list", mode.msg(lang), deck),
+            Text::ChallengeRemoved { mode, deck } => format!("[{}] {} removed from the challenge list", mode.msg(lang), deck),
+            Text::ChallengeMissing { mode, deck } => format!("[{}] {} is not in the challenge list", mode.msg(lang), deck),
+            Text::MarkedDone { mode, deck } => format!("[{}] {} recorded as done", mode.msg(lang), deck),
+            Text::InvalidDeckName => String::from("Enter a deck name of up to 30 characters and 64 bytes (21 Korean characters). e.g. /markdone My deck"),
+
+            Text::InvalidAlias => String::from("Separate the old and new name with =>. e.g. /alias Old name => New name"),
+            Text::AlreadySameDeck => String::from("These names already refer to the same deck"),
+            Text::AliasMerged { old, new } => format!("Records of {} merged into {}", old, new),
+
+            Text::InvalidExportFormat => String::from("Only csv and json are supported. e.g. /export json"),
+            Text::NothingToExport => String::from("No completion records to export"),
+            Text::ExportCaption(count) => format!("{} completion records", count),
+            Text::AskImportFile => String::from("Send a csv or json file to import. It must have the same format as the /export file"),
+            Text::FileTooLarge => String::from("Only files up to 1MB can be imported"),
+            Text::ImportDone => String::from("Import finished"),
+            Text::ImportSummary { mode, imported, skipped } => format!("[{}] {} added, {} skipped as duplicates", mode.msg(lang), imported, skipped),
+            Text::InvalidJson(reason) => format!("Cannot read the json file. {}", reason),
+            Text::InvalidCsvLine { line, reason } => format!("Cannot read line {}. {}", line, reason),
+            Text::NothingToImport => String::from("No records to import"),
+            Text::InvalidRecord { index, reason } => format!("Invalid record #{}. {}", index, reason.en()),
+            Text::InvalidRecordDeck => String::from("Deck name must be 1 to 30 characters and at most 64 bytes (21 Korean characters)"),
+            Text::InvalidRecordTimestamp => String::from("created_at must be in YYYY-MM-DD HH:MM:SS format"),
+            Text::InvalidRecordPlacement => String::from("Placement must be between 1 and 8"),
+
+            Text::DeckCard { name, tier, champions } => format!("{}\nTier : {}\nComposition : {}", name, tier.as_deref().unwrap_or("-"), join_or_dash(champions)),
+            Text::DeckCardSummary { tier, champions } => format!("Tier {} · {}", tier.as_deref().unwrap_or("-"), join_or_dash(champions)),
+
+            Text::CurrentLang(lang) => format!("Current language : {}. e.g. /lang ko", lang.code()),
+            Text::LangChanged(_) => String::from("Language changed to English"),
+            Text::InvalidLang => String::from("Supported languages are ko and en. e.g. /lang ko"),
+
+            Text::UnknownUser => String::from("Cannot tell who to record this for"),
+            Text::LeaderboardTitle { mode, season } => format!("[{}] {} leaderboard", mode.msg(lang), season.as_deref().map(|name| format!("Season {}", name)).unwrap_or(String::from("Current season"))),
+            Text::LeaderboardRow { rank, name, done } => format!("{}. {} — {}", rank, name, done),
+            Text::EmptyLeaderboard(mode) => format!("[{}] No completed decks in this chat yet", mode.msg(lang)),
+
+            Text::InvalidSchedule => String::from("Check the time, offset and weekday. e.g. /subscribe 09:00 +09:00 or /subscribe mon 21:00"),
+            Text::Subscribed { time, offset, weekday } => format!("Digest will be sent {} at {} (UTC{}). Stop with /unsubscribe", weekday.map(|day| format!("every {}", day)).unwrap_or(String::from("daily")), time, offset),
+            Text::Unsubscribed => String::from("Digest subscription canceled"),
+            Text::NotSubscribed => String::from("No digest subscription in this chat"),
+            Text::PatchReleased(version) => format!("Meta for the new patch {} is out. Check it with /update", version),
+            Text::PbeStarted => String::from("PBE meta is up. Check it with /update pbe"),
+            Text::PbeEnded => String::from("PBE meta is gone"),
+            Text::AutoSwitched(mode) => format!("Switched to {} automatically", mode.msg(lang)),
+            Text::Digest { mode, normal, special, yesterday } => format!("[{}] Daily digest\nNext normal deck : {}\nRemaining special decks : {}\nCompleted yesterday : {}", mode.msg(lang), normal.as_deref().unwrap_or("-"), join_or_dash(special), join_or_dash(yesterday)),
+            Text::DigestWithoutDecks { mode, yesterday } => format!("[{}] Daily digest\nRemaining decks are skipped because the deck list could not be loaded\nCompleted yesterday : {}", mode.msg(lang), join_or_dash(yesterday)),
+
+            Text::CrawlNetwork => String::from("Cannot reach lolchess.gg. Please try again later"),
+            Text::CrawlStatus(status) => format!("lolchess.gg responded with an error ({}). Please try again later", status),
+            Text::CrawlBroken => String::from("Cannot find the deck list. Update the path with /fix"),
+            Text::StorageConnection => String::from("Cannot connect to the record storage. Please try again later"),
+            Text::StorageFailed => String::from("The record storage failed to process the request"),
+            Text::Unknown => String::from("An unknown error occurred"),
+        }
+    }
+}
+
+fn weekday_ko(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "매주 월요일",
+        Weekday::Tue => "매주 화요일",
+        Weekday::Wed => "매주 수요일",
+        Weekday::Thu => "매주 목요일",
+        Weekday::Fri => "매주 금요일",
+        Weekday::Sat => "매주 토요일",
+        Weekday::Sun => "매주 일요일",
+    }
+}
+
+fn join_or_dash(items: &[String]) -> String {
+    if items.is_empty() {
+        String::from("-")
+    } else {
+        items.join(", ")
+    }
+}
+
+/// /help 에 보여줄 커맨드 설명. 영어 설명은 Command의 description을 그대로 사용
+pub fn command_description(lang: Lang, command: &str) -> Option<&'static str> {
+    if lang != Lang::Ko {
+        return None;
+    }
+    let description = match command.trim_start_matches('/') {
+        "help" => "이 도움말을 보여줍니다",
+        "mode" => "현재 모드를 보여줍니다",
+        "switch" => "모드를 전환합니다. 모드를 지정할 수도 있습니다. 예) /switch pbe",
+        "update" => "갱신된 덱 목록을 가져옵니다. 다른 모드의 목록도 볼 수 있습니다. 예) /update pbe",
+        "reset" => "완료 이력을 삭제합니다",
+        "undo_reset" => "마지막 초기화로 삭제된 이력을 복구합니다",
+        "done" => "완료한 덱을 보여줍니다. 최근 n개만 볼 수도 있습니다. 예) /done 10",
+        "fix" => "덱 목록 경로를 갱신합니다",
+        "newseason" => "현재 시즌을 종료하고 새 시즌을 시작합니다. 예) /newseason 시즌14",
+        "seasons" => "지난 시즌의 완료 덱을 봅니다",
+        "placements" => "덱별 등수 통계를 보여줍니다",
+        "stats" => "모드별 완료 통계를 보여줍니다",
+        "progress" => "현재 메타의 완료/남은 덱을 보여줍니다",
+        "challenge" => "도전 목록에 덱을 추가하거나 목록을 봅니다. 예) /challenge 나만의 덱",
+        "unchallenge" => "도전 목록에서 덱을 제거합니다. 예) /unchallenge 나만의 덱",
+        "markdone" => "아무 덱이나 완료로 기록합니다. 예) /markdone 나만의 덱",
+        "export" => "완료 이력을 csv 또는 json으로 내보냅니다. 예) /export json",
+        "import" => "csv 또는 json 파일에서 완료 이력을 가져옵니다",
+        "alias" => "이름이 바뀐 덱의 기록을 병합합니다. 예) /alias 옛 이름 => 새 이름",
+        "lang" => "봇 언어를 변경합니다. 예) /lang en",
+        "subscribe" => "매일 또는 매주 요약을 받습니다. 예) /subscribe 09:00 +09:00",
+        "unsubscribe" => "요약 구독을 해지합니다",
+        "leaderboard" => "이 채팅 멤버의 현재 모드, 현재 시즌 완료 순위를 보여줍니다",
+        _ => return None,
+    };
+    Some(description)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lang_parse_test() {
+        assert_eq!(Lang::parse(" EN "), Some(Lang::En));
+        assert_eq!(Lang::parse("ko"), Some(Lang::Ko));
+        assert_eq!(Lang::parse("jp"), None);
+    }
+
+    #[test]
+    fn render_test() {
+        let text = Text::ResetDone { mode: Mode::pbe, count: 3, minutes: 10 };
+
+        assert_eq!(text.render(Lang::Ko), "모드 pbe 모드에 대한 이력 3건 삭제 완료. 10분 안에 /undo_reset 으로 되돌릴 수 있습니다");
+        assert_eq!(text.render(Lang::En), "Deleted 3 records of pbe mode. You can restore them with /undo_reset within 10 minutes");
+    }
+
+    #[test]
+    fn nested_render_test() {
+        let text = Text::InvalidRecord { index: 2, reason: Box::new(Text::InvalidRecordPlacement) };
+
+        assert_eq!(text.render(Lang::Ko), "2번째 기록 오류. 등수는 1~8 사이여야 합니다");
+        assert_eq!(text.render(Lang::En), "Invalid record #2. Placement must be between 1 and 8");
+    }
+
+    #[test]
+    fn command_description_test() {
+        assert_eq!(command_description(Lang::Ko, "/mode"), Some("현재 모드를 보여줍니다"));
+        assert_eq!(command_description(Lang::En, "/mode"), None);
+        assert_eq!(command_description(Lang::Ko, "/unknown"), None);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::db::db::MemberScore;
+
+/// 리더보드에 보여줄 최대 인원
+pub const MAX_RANKS: usize = 20;
+
+/// 완료 수가 많은 순서. 같은 수면 같은 등수이고 다음 등수는 건너뜀 (1, 1, 3)
+pub fn rank(mut scores: Vec<MemberScore>) -> Vec<(usize, MemberScore)> {
+    scores.sort_by(|a, b| b.done.cmp(&a.done).then_with(|| a.name.cmp(&b.name)));
+
+    let mut ranked: Vec<(usize, MemberScore)> = Vec::with_capacity(scores.len());
+    for (i, score) in scores.into_iter().enumerate() {
+        let rank = match ranked.last() {
+            Some((rank, previous)) if previous.done == score.done => *rank,
+            _ => i + 1,
+        };
+        ranked.push((rank, score));
+    }
+    ranked.truncate(MAX_RANKS);
+    ranked
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn score(name: &str, done: usize) -> MemberScore {
+        MemberScore { name: String::from(name), done }
+    }
+
+    #[test]
+    fn rank_test() {
+        let ranked = rank(vec![score("b", 3), score("c", 5), score("a", 3), score("d", 1)]);
+
+        let ranks: Vec<(usize, &str)> = ranked.iter().map(|(rank, score)| (*rank, score.name.as_str())).collect();
+        assert_eq!(ranks, vec![(1, "c"), (2, "a"), (2, "b"), (4, "d")]);
+    }
+
+    #[test]
+    fn empty_rank_test() {
+        assert!(rank(Vec::new()).is_empty());
+    }
+}
@@ -1,2 +1,13 @@
+pub mod access;
 pub mod bot;
-pub mod traits;
\ No newline at end of file
+pub mod deck;
+pub mod digest;
+pub mod error;
+pub mod i18n;
+pub mod leaderboard;
+pub mod progress;
+pub mod stats;
+pub mod traits;
+pub mod transfer;
+pub mod watcher;
+pub mod webhook;
\ No newline at end of file
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use super::{deck::DeckMatcher, i18n::{Lang, Text}, stats::is_special};
+
+/// 한 페이지에 보여줄 덱 수
+pub const PAGE_SIZE: usize = 15;
+const BAR_WIDTH: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub name: String,
+    pub done: bool,
+    pub special: bool,
+}
+
+/// 메타 덱 목록에 완료 여부를 표시. 일반 덱을 먼저, 특수 덱을 뒤에 두고 각 그룹은 메타 순서 유지
+pub fn checklist(meta: &[String], done: &[String], matcher: &DeckMatcher) -> Vec<ChecklistItem> {
+    let flags = matcher.done_flags(meta, done);
+
+    let mut items: Vec<ChecklistItem> = meta.iter()
+        .zip(flags)
+        .map(|(deck, done)| ChecklistItem {
+            name: deck.clone(),
+            done,
+            special: is_special(deck),
+        })
+        .collect();
+    items.sort_by_key(|item| item.special);
+    items
+}
+
+pub fn progress_bar(done: usize, total: usize) -> String {
+    let filled = (done * BAR_WIDTH).checked_div(total).unwrap_or(0);
+    let percent = (done * 100).checked_div(total).unwrap_or(0);
+    format!("{}{} {}/{} ({}%)", "▓".repeat(filled), "░".repeat(BAR_WIDTH - filled), done, total, percent)
+}
+
+pub fn page_count(items: &[ChecklistItem]) -> usize {
+    items.len().div_ceil(PAGE_SIZE).max(1)
+}
+
+/// page는 0부터 시작. 범위를 벗어나면 마지막 페이지를 보여줌
+pub fn render_page(title: &str, items: &[ChecklistItem], page: usize, lang: Lang) -> String {
+    let page = page.min(page_count(items) - 1);
+    let done = items.iter().filter(|item| item.done).count();
+
+    let mut text = format!("{}\n{}", title, progress_bar(done, items.len()));
+
+    let mut group: Option<bool> = None;
+    for item in items.iter().skip(page * PAGE_SIZE).take(PAGE_SIZE) {
+        if group != Some(item.special) {
+            let header = if item.special { Text::SpecialDecks } else { Text::NormalDecks }.render(lang);
+            let group_items = items.iter().filter(|i| i.special == item.special);
+            let group_done = group_items.clone().filter(|i| i.done).count();
+            text.push_str(&format!("\n\n[{}] {}/{}", header, group_done, group_items.count()));
+            group = Some(item.special);
+        }
+        text.push_str(&format!("\n{} {}", if item.done { "✅" } else { "⬜" }, item.name));
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn checklist_test() {
+        let meta = vec![String::from("[상징] A"), String::from("B"), String::from("C")];
+        let done = vec![String::from("C"), String::from("[상징] A")];
+
+        let items = checklist(&meta, &done, &DeckMatcher::new(HashMap::new()));
+
+        assert_eq!(items.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(), vec!["B", "C", "[상징] A"]);
+        assert_eq!(items.iter().map(|i| i.done).collect::<Vec<_>>(), vec![false, true, true]);
+    }
+
+    #[test]
+    fn progress_bar_test() {
+        assert_eq!(progress_bar(3, 4), "▓▓▓▓▓▓▓░░░ 3/4 (75%)");
+        assert_eq!(progress_bar(0, 0), "░░░░░░░░░░ 0/0 (0%)");
+    }
+
+    #[test]
+    fn render_page_test() {
+        let meta: Vec<String> = (0..20).map(|i| format!("deck{}", i)).chain([String::from("[상징] A")]).collect();
+        let items = checklist(&meta, &[String::from("deck0")], &DeckMatcher::new(HashMap::new()));
+
+        assert_eq!(page_count(&items), 2);
+
+        let first = render_page("진행도", &items, 0, Lang::Ko);
+        assert!(first.contains("[일반 덱] 1/20"));
+        assert!(first.contains("✅ deck0"));
+        assert!(!first.contains("[특수 덱]"));
+
+        let second = render_page("진행도", &items, 5, Lang::Ko);
+        assert!(second.contains("⬜ deck19"));
+        assert!(second.contains("[특수 덱] 0/1"));
+    }
+}
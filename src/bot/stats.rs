@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::db::db::{DoneRecord, Game};
+
+use super::deck::DeckMatcher;
+
+/// 이름이 [태그]로 시작하는 덱은 특수 덱
+pub fn is_special(deck: &str) -> bool {
+    deck.starts_with('[')
+}
+
+/// 모드별 완료 통계
+#[derive(Debug, PartialEq)]
+pub struct DoneStats {
+    pub total: usize,
+    pub this_week: usize,
+    pub normal: usize,
+    pub special: usize,
+    pub longest_streak: usize,
+    /// 현재 메타 덱 중 완료한 덱 수와 전체 덱 수. 메타를 불러오지 못하면 None
+    pub meta_progress: Option<(usize, usize)>,
+}
+
+impl DoneStats {
+
+    pub fn new(records: &[DoneRecord], meta: Option<&[String]>, matcher: &DeckMatcher, today: NaiveDate) -> Self {
+        let dates: Vec<NaiveDate> = records.iter().filter_map(record_date).collect();
+        let week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+
+        let done: Vec<String> = records.iter().map(|r| r.name.clone()).collect();
+        let meta_progress = meta.map(|meta| {
+            (matcher.done_flags(meta, &done).into_iter().filter(|flag| *flag).count(), meta.len())
+        });
+
+        let special = records.iter().filter(|r| is_special(&r.name)).count();
+
+        Self {
+            total: records.len(),
+            this_week: dates.iter().filter(|date| **date >= week_start && **date <= today).count(),
+            normal: records.len() - special,
+            special,
+            longest_streak: longest_streak(&dates),
+            meta_progress,
+        }
+    }
+}
+
+fn record_date(record: &DoneRecord) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(record.created_at.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// 하루도 빠짐없이 완료 기록이 있는 가장 긴 연속 일수
+pub fn longest_streak(dates: &[NaiveDate]) -> usize {
+    let mut days: Vec<NaiveDate> = dates.to_vec();
+    days.sort();
+    days.dedup();
+
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for day in days {
+        current = match previous {
+            Some(prev) if prev.succ_opt() == Some(day) => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        previous = Some(day);
+    }
+    longest
+}
+
+/// 덱별 등수 요약
+#[derive(Debug, PartialEq)]
+pub struct DeckPlacement {
+    pub name: String,
+    pub games: usize,
+    pub average: f64,
+    pub top4_rate: f64,
+    /// 처음 완료로 기록될 때까지 플레이한 판 수
+    pub games_to_complete: Option<usize>,
+}
+
+/// 기록 순서대로 정렬된 판들을 덱별로 묶어 요약. 덱은 처음 플레이한 순서대로
+pub fn summarize_placements(games: &[Game]) -> Vec<DeckPlacement> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut totals: Vec<(usize, usize)> = Vec::new(); // (등수 합, top4 횟수)
+    let mut summary: Vec<DeckPlacement> = Vec::new();
+
+    for game in games {
+        let i = *index.entry(&game.name).or_insert_with(|| {
+            summary.push(DeckPlacement {
+                name: game.name.clone(),
+                games: 0,
+                average: 0.0,
+                top4_rate: 0.0,
+                games_to_complete: None,
+            });
+            totals.push((0, 0));
+            summary.len() - 1
+        });
+
+        let deck = &mut summary[i];
+        deck.games += 1;
+        totals[i].0 += game.placement as usize;
+        if game.placement <= 4 {
+            totals[i].1 += 1;
+        }
+        if game.completed && deck.games_to_complete.is_none() {
+            deck.games_to_complete = Some(deck.games);
+        }
+    }
+
+    for (deck, (sum, top4)) in summary.iter_mut().zip(totals) {
+        deck.average = sum as f64 / deck.games as f64;
+        deck.top4_rate = top4 as f64 / deck.games as f64;
+    }
+    summary
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn game(name: &str, placement: u8, completed: bool) -> Game {
+        Game { name: name.to_string(), placement, completed }
+    }
+
+    fn record(name: &str, created_at: &str) -> DoneRecord {
+        DoneRecord { name: name.to_string(), created_at: created_at.to_string(), placement: None }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn longest_streak_test() {
+        let dates = vec![
+            date("2024-12-01"),
+            date("2024-12-02"),
+            date("2024-12-02"),
+            date("2024-12-05"),
+            date("2024-12-06"),
+            date("2024-12-07"),
+        ];
+        assert_eq!(longest_streak(&dates), 3);
+        assert_eq!(longest_streak(&[]), 0);
+    }
+
+    #[test]
+    fn done_stats_test() {
+        let records = vec![
+            record("[상징] 6자동기계 코그모 리롤덱", "2024-12-20 10:00:00"),
+            record("A", "2024-12-23 10:00:00"),
+            record("B", "2024-12-24 23:59:59"),
+        ];
+        let meta = vec![String::from("A"), String::from("C")];
+
+        // memo. 2024-12-25는 수요일
+        let stats = DoneStats::new(&records, Some(&meta), &DeckMatcher::new(HashMap::new()), date("2024-12-25"));
+
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.this_week, 2);
+        assert_eq!(stats.normal, 2);
+        assert_eq!(stats.special, 1);
+        assert_eq!(stats.longest_streak, 2);
+        assert_eq!(stats.meta_progress, Some((1, 2)));
+    }
+
+    #[test]
+    fn summarize_placements_test() {
+        let games = vec![
+            game("A", 6, false),
+            game("B", 1, true),
+            game("A", 3, false),
+            game("A", 2, true),
+            game("A", 5, false),
+        ];
+
+        let summary = summarize_placements(&games);
+
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].name, "A");
+        assert_eq!(summary[0].games, 4);
+        assert_eq!(summary[0].average, 4.0);
+        assert_eq!(summary[0].top4_rate, 0.5);
+        assert_eq!(summary[0].games_to_complete, Some(3));
+        assert_eq!(summary[1].games_to_complete, Some(1));
+    }
+
+    #[test]
+    fn summarize_without_completion_test() {
+        let summary = summarize_placements(&[game("A", 8, false)]);
+        assert_eq!(summary[0].games_to_complete, None);
+        assert_eq!(summary[0].top4_rate, 0.0);
+    }
+}
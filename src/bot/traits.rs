@@ -1,4 +1,7 @@
 use std::fmt::format;
+use serde::{Deserialize, Serialize};
+
+use super::i18n::{Lang, Text};
 
 
 pub trait DeckLoader<E: std::error::Error> {
@@ -10,7 +13,7 @@ pub trait Storage <E>{
 
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Mode{
     main,
     pbe
@@ -24,10 +27,15 @@ impl Mode {
         }
     }
 
-    pub fn msg(&self) -> String {
-        match self {
-            Mode::main => return format!("정규 모드"),
-            Mode::pbe => return format!("pbe 모드"),
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "main" | "정규" => Some(Mode::main),
+            "pbe" => Some(Mode::pbe),
+            _ => None,
         }
     }
+
+    pub fn msg(&self, lang: Lang) -> String {
+        Text::ModeName(self.clone()).render(lang)
+    }
 }
\ No newline at end of file
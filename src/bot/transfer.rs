@@ -0,0 +1,148 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::{deck::valid_name, error::UserError, i18n::Text, traits::Mode};
+
+/// 가져올 파일의 최대 크기
+pub const MAX_IMPORT_BYTES: u32 = 1024 * 1024;
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 내보내기/가져오기 파일의 한 줄
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub mode: Mode,
+    pub deck: String,
+    /// YYYY-MM-DD HH:MM:SS
+    pub created_at: String,
+    pub placement: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+
+    /// 형식을 지정하지 않으면 csv
+    pub fn parse(input: &str) -> Result<Self, UserError> {
+        match input.trim().to_lowercase().as_str() {
+            "" | "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(UserError::from(Text::InvalidExportFormat)),
+        }
+    }
+
+    /// 파일 이름의 확장자로 판단하고, 없으면 내용의 첫 글자로 판단
+    pub fn detect(file_name: Option<&str>, content: &[u8]) -> Self {
+        match file_name.and_then(|name| name.rsplit_once('.')).map(|(_, ext)| ext.to_lowercase()) {
+            Some(ext) if ext == "json" => Self::Json,
+            Some(ext) if ext == "csv" => Self::Csv,
+            _ => match content.iter().find(|b| !b.is_ascii_whitespace()) {
+                Some(b'[') => Self::Json,
+                _ => Self::Csv,
+            },
+        }
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Csv => "lolche_done.csv",
+            Self::Json => "lolche_done.json",
+        }
+    }
+}
+
+pub fn export(records: &[ExportRecord], format: ExportFormat) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    match format {
+        ExportFormat::Json => Ok(serde_json::to_vec_pretty(records)?),
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for record in records {
+                writer.serialize(record)?;
+            }
+            Ok(writer.into_inner().map_err(|e| e.into_error())?)
+        }
+    }
+}
+
+/// 파일을 읽고 모든 줄을 검증. 하나라도 잘못되면 아무것도 가져오지 않음
+pub fn parse(content: &[u8], format: ExportFormat) -> Result<Vec<ExportRecord>, UserError> {
+    let records: Vec<ExportRecord> = match format {
+        ExportFormat::Json => serde_json::from_slice(content)
+            .map_err(|e| UserError::from(Text::InvalidJson(e.to_string())))?,
+        ExportFormat::Csv => csv::Reader::from_reader(content)
+            .deserialize()
+            .enumerate()
+            .map(|(i, row)| row.map_err(|e| UserError::from(Text::InvalidCsvLine { line: i + 2, reason: e.to_string() })))
+            .collect::<Result<_, _>>()?,
+    };
+
+    if records.is_empty() {
+        return Err(UserError::from(Text::NothingToImport));
+    }
+
+    records.into_iter()
+        .enumerate()
+        .map(|(i, record)| validate(record).map_err(|reason| UserError::from(Text::InvalidRecord { index: i + 1, reason: Box::new(reason) })))
+        .collect()
+}
+
+fn validate(mut record: ExportRecord) -> Result<ExportRecord, Text> {
+    // memo. 직접 입력한 덱 이름과 같은 기준. 버튼 데이터 64바이트 제한 때문에 한글은 21자까지
+    record.deck = valid_name(&record.deck).ok_or(Text::InvalidRecordDeck)?;
+    if NaiveDateTime::parse_from_str(&record.created_at, TIMESTAMP_FORMAT).is_err() {
+        return Err(Text::InvalidRecordTimestamp);
+    }
+    if record.placement.is_some_and(|placement| !(1..=8).contains(&placement)) {
+        return Err(Text::InvalidRecordPlacement);
+    }
+    Ok(record)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn records() -> Vec<ExportRecord> {
+        vec![
+            ExportRecord { mode: Mode::main, deck: String::from("[상징] 6자동기계 코그모 리롤덱"), created_at: String::from("2024-12-20 10:00:00"), placement: Some(3) },
+            ExportRecord { mode: Mode::pbe, deck: String::from("징크스, 리롤"), created_at: String::from("2024-12-21 23:59:59"), placement: None },
+        ]
+    }
+
+    #[test]
+    fn csv_round_trip_test() {
+        let content = export(&records(), ExportFormat::Csv).unwrap();
+
+        assert!(String::from_utf8_lossy(&content).starts_with("mode,deck,created_at,placement\n"));
+        assert_eq!(parse(&content, ExportFormat::Csv).unwrap(), records());
+    }
+
+    #[test]
+    fn json_round_trip_test() {
+        let content = export(&records(), ExportFormat::Json).unwrap();
+
+        assert_eq!(ExportFormat::detect(None, &content), ExportFormat::Json);
+        assert_eq!(parse(&content, ExportFormat::Json).unwrap(), records());
+    }
+
+    #[test]
+    fn invalid_record_test() {
+        let content = b"mode,deck,created_at,placement\nmain,A,2024-12-20 10:00:00,9\n";
+        assert!(parse(content, ExportFormat::Csv).unwrap_err().to_string().starts_with("1번째 기록 오류"));
+
+        let content = b"mode,deck,created_at,placement\nranked,A,2024-12-20 10:00:00,\n";
+        assert!(parse(content, ExportFormat::Csv).unwrap_err().to_string().starts_with("2번째 줄"));
+
+        let content = br#"[{"mode":"main","deck":"A","created_at":"2024/12/20","placement":null}]"#;
+        assert!(parse(content, ExportFormat::Json).is_err());
+
+        // memo. 30자 이내여도 64바이트를 넘으면 거절
+        let content = format!("mode,deck,created_at,placement\nmain,{},2024-12-20 10:00:00,\n", "가".repeat(22));
+        assert!(parse(content.as_bytes(), ExportFormat::Csv).is_err());
+        let content = format!("mode,deck,created_at,placement\nmain,{},2024-12-20 10:00:00,\n", "가".repeat(21));
+        assert!(parse(content.as_bytes(), ExportFormat::Csv).is_ok());
+    }
+}
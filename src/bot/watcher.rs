@@ -0,0 +1,205 @@
+use std::{sync::Arc, time::Duration};
+
+use teloxide::prelude::*;
+
+use crate::{crawl::{crawl::LolcheggCrawler, error::CrawlError}, db::db::Storage};
+
+use super::{error::BotError, i18n::Text, traits::Mode};
+
+const PATCH_KEY: &str = "patch";
+const PBE_KEY: &str = "pbe";
+
+/// 메타 페이지 감시 설정
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub interval: Duration,
+    /// pbe 시작/종료 시 모드를 자동으로 전환
+    pub auto_switch: bool,
+}
+
+/// 한 번 조회한 메타 페이지 상태. 조회에 실패한 값은 None
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WatchState {
+    pub patch: Option<String>,
+    pub pbe_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    PatchReleased(String),
+    PbeStarted,
+    PbeEnded,
+}
+
+impl Change {
+    /// 자동 전환할 모드. 새 패치는 정규 모드 목록만 바뀌므로 전환하지 않음
+    pub fn target_mode(&self) -> Option<Mode> {
+        match self {
+            Change::PatchReleased(_) => None,
+            Change::PbeStarted => Some(Mode::pbe),
+            Change::PbeEnded => Some(Mode::main),
+        }
+    }
+
+    fn text(&self) -> Text {
+        match self {
+            Change::PatchReleased(version) => Text::PatchReleased(version.clone()),
+            Change::PbeStarted => Text::PbeStarted,
+            Change::PbeEnded => Text::PbeEnded,
+        }
+    }
+}
+
+// memo. 처음 조회한 값이나 조회에 실패한 값은 비교하지 않음
+pub fn detect(previous: &WatchState, current: &WatchState) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if let (Some(before), Some(after)) = (&previous.patch, &current.patch) {
+        if before != after {
+            changes.push(Change::PatchReleased(after.clone()));
+        }
+    }
+    match (previous.pbe_active, current.pbe_active) {
+        (Some(false), Some(true)) => changes.push(Change::PbeStarted),
+        (Some(true), Some(false)) => changes.push(Change::PbeEnded),
+        _ => {}
+    }
+    changes
+}
+
+/// 주기적으로 메타 페이지를 확인하고 바뀐 점을 요약 구독 채팅에 알림
+pub async fn run(bot: Bot, stg: Storage, crawler: Arc<LolcheggCrawler>, options: WatchOptions) {
+    let mut interval = tokio::time::interval(options.interval);
+    loop {
+        interval.tick().await;
+        if let Err(e) = watch(&bot, &stg, &crawler, &options).await {
+            log::warn!("fail to watch meta page. {}", e);
+        }
+    }
+}
+
+async fn watch(bot: &Bot, stg: &Storage, crawler: &Arc<LolcheggCrawler>, options: &WatchOptions) -> Result<(), BotError> {
+    let previous = stg.blocking(|stg| Ok(WatchState {
+        patch: stg.select_watch_state(PATCH_KEY)?,
+        pbe_active: stg.select_watch_state(PBE_KEY)?.map(|value| value == "true"),
+    })).await?;
+
+    let loader = crawler.clone();
+    let current = tokio::task::spawn_blocking(move || current_state(&loader)).await?;
+
+    let state = current.clone();
+    stg.blocking(move |stg| {
+        if let Some(patch) = &state.patch {
+            stg.upsert_watch_state(PATCH_KEY, patch)?;
+        }
+        if let Some(active) = state.pbe_active {
+            stg.upsert_watch_state(PBE_KEY, &active.to_string())?;
+        }
+        Ok(())
+    }).await?;
+
+    let changes = detect(&previous, &current);
+    if changes.is_empty() {
+        return Ok(());
+    }
+    log::info!("meta page changed. {:?}", changes);
+
+    // memo. 모드는 모든 채팅이 공유하므로 마지막 변경 기준으로 한 번만 전환
+    let target = changes.iter().rev().find_map(Change::target_mode).filter(|_| options.auto_switch);
+    let switched = stg.blocking(move |stg| match target {
+        Some(mode) if stg.select_mode()? != mode => {
+            stg.upsert_mode(&mode)?;
+            Ok(Some(mode))
+        }
+        _ => Ok(None),
+    }).await?;
+
+    for sub in stg.blocking(Storage::retrieve_subscriptions).await? {
+        let chat_id = sub.chat_id;
+        let lang = stg.blocking(move |stg| stg.select_lang(chat_id)).await?;
+        let mut text = changes.iter().map(|change| change.text().render(lang)).collect::<Vec<String>>().join("\n");
+        if let Some(mode) = &switched {
+            text.push_str(&format!("\n{}", Text::AutoSwitched(mode.clone()).render(lang)));
+        }
+        if let Err(e) = bot.send_message(ChatId(sub.chat_id), text).await {
+            log::warn!("fail to notify meta change. chat: {}, error: {}", sub.chat_id, e);
+        }
+    }
+    Ok(())
+}
+
+fn current_state(crawler: &LolcheggCrawler) -> WatchState {
+    let patch = crawler.patch_version()
+        .map_err(|e| log::warn!("fail to read patch version. {}", e))
+        .ok()
+        .flatten();
+
+    // memo. 중간에 경로가 갱신되어도 두 페이지를 같은 selector로 조회
+    let selector = crawler.css_path();
+    let pbe_active = pbe_active(
+        crawler.deck_names(&Mode::pbe, Some(&selector)),
+        || crawler.deck_names(&Mode::main, Some(&selector)),
+    );
+
+    WatchState { patch, pbe_active }
+}
+
+/// pbe 목록이 비어 있으면 pbe 기간이 아닌 것으로 봄
+/// 경로가 깨져도 결과가 비므로 같은 selector로 정규 모드 목록이 조회될 때만 판단. 그 외에는 알 수 없음으로 처리
+fn pbe_active<F>(pbe: Result<Vec<String>, CrawlError>, main: F) -> Option<bool>
+where
+    F: FnOnce() -> Result<Vec<String>, CrawlError>,
+{
+    match pbe {
+        Ok(decks) if !decks.is_empty() => Some(true),
+        Ok(_) | Err(CrawlError::EmptyResult) => match main() {
+            Ok(decks) if !decks.is_empty() => Some(false),
+            Ok(_) | Err(_) => {
+                log::warn!("pbe decks are empty but main decks are not found either. css path may be broken");
+                None
+            }
+        },
+        Err(e) => {
+            log::warn!("fail to read pbe decks. {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn state(patch: Option<&str>, pbe_active: Option<bool>) -> WatchState {
+        WatchState { patch: patch.map(String::from), pbe_active }
+    }
+
+    #[test]
+    fn detect_test() {
+        assert!(detect(&WatchState::default(), &state(Some("14.24"), Some(true))).is_empty());
+        assert!(detect(&state(Some("14.24"), Some(false)), &state(None, None)).is_empty());
+
+        assert_eq!(
+            detect(&state(Some("14.23"), Some(false)), &state(Some("14.24"), Some(true))),
+            vec![Change::PatchReleased(String::from("14.24")), Change::PbeStarted]
+        );
+        assert_eq!(detect(&state(Some("14.24"), Some(true)), &state(Some("14.24"), Some(false))), vec![Change::PbeEnded]);
+    }
+
+    #[test]
+    fn pbe_active_test() {
+        let decks = || Ok(vec![String::from("리롤 징크스")]);
+        assert_eq!(pbe_active(decks(), || panic!("main is not needed")), Some(true));
+        assert_eq!(pbe_active(Err(CrawlError::EmptyResult), decks), Some(false));
+        assert_eq!(pbe_active(Ok(Vec::new()), decks), Some(false));
+        // memo. 경로가 깨지면 정규 모드도 비어 있으므로 판단하지 않음
+        assert_eq!(pbe_active(Err(CrawlError::EmptyResult), || Err(CrawlError::EmptyResult)), None);
+        assert_eq!(pbe_active(Err(CrawlError::EmptyResult), || Ok(Vec::new())), None);
+    }
+
+    #[test]
+    fn target_mode_test() {
+        assert_eq!(Change::PbeStarted.target_mode(), Some(Mode::pbe));
+        assert_eq!(Change::PbeEnded.target_mode(), Some(Mode::main));
+        assert_eq!(Change::PatchReleased(String::from("14.24")).target_mode(), None);
+    }
+}
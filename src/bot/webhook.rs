@@ -0,0 +1,133 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use reqwest::Url;
+use teloxide::{prelude::*, update_listeners::{webhooks, UpdateListener}};
+
+use super::error::BotError;
+
+/// long polling 대신 텔레그램이 업데이트를 보내줄 주소
+#[derive(Debug, Clone)]
+pub struct WebhookOptions {
+    /// 리버스 프록시가 전달하는 내부 주소
+    pub listen: SocketAddr,
+    /// 텔레그램에 등록할 공개 주소. 경로는 그대로 라우팅 경로로 사용
+    pub url: Url,
+    /// 없으면 teloxide가 실행할 때마다 새로 생성
+    pub secret_token: Option<String>,
+}
+
+impl WebhookOptions {
+    pub fn new(listen: SocketAddr, url: &str, secret_token: Option<String>) -> Result<Self, String> {
+        let url = Url::parse(url).map_err(|e| format!("invalid webhook url '{}'. {}", url, e))?;
+        if url.scheme() != "https" {
+            return Err(format!("webhook url must be https. got '{}'", url));
+        }
+        // memo. teloxide는 잘못된 토큰이면 panic 하므로 미리 확인
+        if let Some(token) = &secret_token {
+            if !is_valid_secret(token) {
+                return Err(String::from("webhook secret_token must be 1-256 characters of A-Z, a-z, 0-9, _ and -"));
+            }
+        }
+        Ok(Self { listen, url, secret_token })
+    }
+
+    fn teloxide(&self) -> webhooks::Options {
+        let options = webhooks::Options::new(self.listen, self.url.clone());
+        match &self.secret_token {
+            Some(token) => options.secret_token(token.clone()),
+            None => options,
+        }
+    }
+}
+
+fn is_valid_secret(token: &str) -> bool {
+    (1..=256).contains(&token.len()) && token.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// 웹훅을 등록하고 listen 주소에서 업데이트를 받음. 종료 시 웹훅은 삭제됨
+/// 주소를 쓸 수 없거나 웹훅 등록이 거절되면 Err
+pub async fn listener(bot: Bot, options: &WebhookOptions) -> Result<impl UpdateListener<Err = Infallible>, BotError> {
+    // memo. teloxide의 webhooks::axum 은 바인딩에 실패하면 별도 태스크에서 panic 하므로 먼저 바인딩
+    let tcp = tokio::net::TcpListener::bind(options.listen).await
+        .map_err(|e| BotError::Internal(format!("fail to bind webhook listener on {}. {}", options.listen, e).into()))?;
+
+    let (mut listener, stop, router) = webhooks::axum_to_router(bot, options.teloxide()).await?;
+    let stop_token = listener.stop_token();
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(tcp, router).with_graceful_shutdown(stop).await {
+            log::error!("webhook listener stopped. {}", e);
+            stop_token.stop();
+        }
+    });
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::StreamExt;
+    use teloxide::{types::UpdateKind, update_listeners::AsUpdateStream};
+
+    const UPDATE: &str = r#"{
+        "update_id": 10001,
+        "message": {
+            "message_id": 7,
+            "date": 1734000000,
+            "chat": { "id": -1001, "type": "group", "title": "lolche" },
+            "from": { "id": 42, "is_bot": false, "first_name": "tester" },
+            "text": "/done"
+        }
+    }"#;
+
+    #[tokio::test]
+    async fn bind_error_test() {
+        let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let options = WebhookOptions::new(tcp.local_addr().unwrap(), "https://bot.example.com/telegram", None).unwrap();
+        // memo. 주소가 이미 사용 중이면 텔레그램에 등록하기 전에 실패
+        assert!(listener(Bot::new(""), &options).await.is_err());
+    }
+
+    #[test]
+    fn options_test() {
+        let listen: SocketAddr = "127.0.0.1:8443".parse().unwrap();
+        assert!(WebhookOptions::new(listen, "https://bot.example.com/telegram", Some(String::from("abc_DEF-123"))).is_ok());
+        assert!(WebhookOptions::new(listen, "http://bot.example.com/telegram", None).is_err());
+        assert!(WebhookOptions::new(listen, "bot.example.com", None).is_err());
+        assert!(WebhookOptions::new(listen, "https://bot.example.com/telegram", Some(String::from("has space"))).is_err());
+        assert!(WebhookOptions::new(listen, "https://bot.example.com/telegram", Some(String::new())).is_err());
+    }
+
+    // memo. 텔레그램에 웹훅을 등록하지 않고 리스너만 띄워서 업데이트를 직접 보냄
+    #[tokio::test]
+    async fn listener_test() {
+        let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = tcp.local_addr().unwrap();
+        let options = WebhookOptions::new(addr, "https://bot.example.com/telegram", Some(String::from("secret"))).unwrap();
+
+        let (mut listener, stop, router) = webhooks::axum_no_setup(options.teloxide());
+        tokio::spawn(async move { axum::serve(tcp, router).with_graceful_shutdown(stop).await });
+
+        let client = reqwest::Client::new();
+        let post = |token: &'static str| client
+            .post(format!("http://{}/telegram", addr))
+            .header("X-Telegram-Bot-Api-Secret-Token", token)
+            .header("Content-Type", "application/json")
+            .body(UPDATE)
+            .send();
+
+        assert_eq!(post("wrong").await.unwrap().status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert_eq!(post("secret").await.unwrap().status(), reqwest::StatusCode::OK);
+
+        let stream = listener.as_stream();
+        futures::pin_mut!(stream);
+        let update = stream.next().await.unwrap().unwrap();
+        assert_eq!(update.id.0, 10001);
+        match update.kind {
+            UpdateKind::Message(msg) => {
+                assert_eq!(msg.chat.id, ChatId(-1001));
+                assert_eq!(msg.text(), Some("/done"));
+            }
+            _ => panic!("unexpected update kind"),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::{error::Error, path::PathBuf, time::Instant};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+use crate::{bot::{deck::{valid_name, MAX_NAME_BYTES, MAX_NAME_CHARS}, traits::Mode}, crawl::crawl::{parse_decks, LolcheggCrawler}, db::{db::{DoneRecord, Storage}, dialogue::DialogueStorage}};
+
+/// 서브커맨드 없이 실행하면 텔레그램 봇을 실행
+#[derive(Parser, Debug)]
+#[command(name = "lolche_bot", about = "Lolche Bot and its administration commands")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List, add or delete completion records
+    Done {
+        #[command(subcommand)]
+        action: DoneAction,
+    },
+    /// Show the current mode, or switch to the given mode
+    Mode {
+        #[arg(value_parser = parse_mode)]
+        mode: Option<Mode>,
+    },
+    /// Crawl the deck list once and print the decks, selector, timing and error
+    Crawl {
+        #[arg(long, default_value = "main", value_parser = parse_mode)]
+        mode: Mode,
+        /// Parse a saved html file instead of fetching the meta page. --mode is ignored
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Use this css selector instead of the current one
+        #[arg(long)]
+        selector: Option<String>,
+        /// Print the result as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Find a new css path to the deck list and print it
+    Discover,
+    /// Create missing tables and columns
+    Migrate,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DoneAction {
+    /// Print completion records in recorded order
+    List {
+        #[arg(long, default_value = "main", value_parser = parse_mode)]
+        mode: Mode,
+        /// Only records of this telegram user and records made before group support
+        #[arg(long)]
+        user: Option<u64>,
+    },
+    /// Record a deck as done. The record is shared by all users
+    Add {
+        name: String,
+        #[arg(long, default_value = "main", value_parser = parse_mode)]
+        mode: Mode,
+    },
+    /// Delete records of a deck
+    Delete {
+        name: String,
+        #[arg(long, default_value = "main", value_parser = parse_mode)]
+        mode: Mode,
+        /// Only records of this telegram user and records made before group support
+        #[arg(long)]
+        user: Option<u64>,
+    },
+    /// Delete every record of the mode without archiving
+    Clear {
+        #[arg(long, default_value = "main", value_parser = parse_mode)]
+        mode: Mode,
+        /// Required to actually delete
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+impl Command {
+    /// 크롤링만 하는 커맨드는 설정 파일과 DB 없이 실행
+    pub fn needs_storage(&self) -> bool {
+        !matches!(self, Command::Crawl { .. } | Command::Discover)
+    }
+}
+
+fn parse_mode(input: &str) -> Result<Mode, String> {
+    Mode::parse(input).ok_or(format!("mode must be main or pbe. got '{}'", input))
+}
+
+/// 크롤링만 하는 커맨드는 stg 없이 실행됨
+pub fn run(command: Command, stg: Option<Storage>) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let storage = || stg.clone().ok_or("storage is not configured");
+
+    match command {
+        Command::Done { action } => done(action, &storage()?)?,
+        Command::Mode { mode: None } => println!("{:?}", storage()?.select_mode()?),
+        Command::Mode { mode: Some(mode) } => {
+            storage()?.upsert_mode(&mode)?;
+            println!("switched to {:?}", mode);
+        }
+        Command::Crawl { mode, file, selector, json } => {
+            let report = crawl_report(&LolcheggCrawler::new(), &mode, file, selector);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{}", report);
+            }
+            if report.error.is_some() {
+                Err("crawl failed")?
+            }
+        }
+        Command::Discover => println!("{}", LolcheggCrawler::new().discover_css_path()?),
+        // memo. 테이블과 컬럼은 Storage::new 와 DialogueStorage::new 에서 만들어짐
+        Command::Migrate => {
+            DialogueStorage::new(&storage()?)?;
+            println!("migration done");
+        }
+    }
+    Ok(())
+}
+
+/// crawl 커맨드 결과. 실패해도 사용한 selector와 걸린 시간을 보여줌
+#[derive(Debug, Serialize)]
+pub struct CrawlReport {
+    pub source: String,
+    pub selector: String,
+    pub elapsed_ms: u128,
+    pub decks: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl std::fmt::Display for CrawlReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "source   : {}", self.source)?;
+        writeln!(f, "selector : {}", self.selector)?;
+        writeln!(f, "elapsed  : {} ms", self.elapsed_ms)?;
+        writeln!(f, "decks    : {}", self.decks.len())?;
+        for (i, deck) in self.decks.iter().enumerate() {
+            writeln!(f, "  {:>2}. {}", i + 1, deck)?;
+        }
+        if let Some(error) = &self.error {
+            writeln!(f, "error    : {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+// memo. 경로 자동 갱신 없이 한 번만 조회하여 selector 문제를 그대로 드러냄
+fn crawl_report(crawler: &LolcheggCrawler, mode: &Mode, file: Option<PathBuf>, selector: Option<String>) -> CrawlReport {
+    let selector = selector.unwrap_or_else(|| crawler.css_path());
+    let started = Instant::now();
+
+    let (source, result) = match file {
+        Some(file) => {
+            let result = std::fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|html| parse_decks(&html, &selector).map_err(|e| e.to_string()));
+            (file.display().to_string(), result)
+        }
+        None => (crawler.url(mode), crawler.deck_names(mode, Some(&selector)).map_err(|e| e.to_string())),
+    };
+
+    let (decks, error) = match result {
+        Ok(decks) => (decks, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    CrawlReport { source, selector, elapsed_ms: started.elapsed().as_millis(), decks, error }
+}
+
+fn done(action: DoneAction, stg: &Storage) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match action {
+        DoneAction::List { mode, user } => {
+            for record in stg.retrieve_done_records(&mode, user)? {
+                println!("{}\t{}\t{}", record.created_at, record.placement.map(|p| p.to_string()).unwrap_or(String::from("-")), record.name);
+            }
+        }
+        DoneAction::Add { name, mode } => {
+            let name = valid_name(&name)
+                .ok_or(format!("deck name must be 1-{} characters and at most {} bytes", MAX_NAME_CHARS, MAX_NAME_BYTES))?;
+            let record = DoneRecord {
+                name: name.clone(),
+                created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                placement: None,
+            };
+            stg.import_done(&mode, &[record], None)?;
+            println!("added {} to {:?}", name, mode);
+        }
+        DoneAction::Delete { name, mode, user } => {
+            stg.delete_record(&mode, name.trim(), user)?;
+            println!("deleted {} from {:?}", name.trim(), mode);
+        }
+        DoneAction::Clear { mode, yes: false } => {
+            println!("{} records of {:?} will be deleted. run again with --yes", stg.count_done(&mode)?, mode);
+        }
+        DoneAction::Clear { mode, yes: true } => {
+            stg.delete_all(&mode)?;
+            println!("deleted all records of {:?}", mode);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_test() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parse_test() {
+        assert!(Cli::try_parse_from(["lolche_bot"]).unwrap().command.is_none());
+
+        let cli = Cli::try_parse_from(["lolche_bot", "done", "delete", "My deck", "--mode", "pbe", "--user", "42"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Done { action: DoneAction::Delete { mode: Mode::pbe, user: Some(42), .. } })));
+
+        let cli = Cli::try_parse_from(["lolche_bot", "crawl"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Crawl { mode: Mode::main, file: None, selector: None, json: false })));
+        assert!(!cli.command.unwrap().needs_storage());
+
+        assert!(Cli::try_parse_from(["lolche_bot", "mode", "ranked"]).is_err());
+    }
+
+    #[test]
+    fn crawl_file_test() {
+        let file = std::env::temp_dir().join("lolche_crawl_file_test.html");
+        std::fs::write(&file, r#"<div><p class="name">리롤 징크스</p><p class="name">6자동기계 코그모</p></div>"#).unwrap();
+        let crawler = LolcheggCrawler::new();
+
+        let report = crawl_report(&crawler, &Mode::main, Some(file.clone()), Some(String::from("p.name")));
+        assert_eq!(report.decks, vec![String::from("리롤 징크스"), String::from("6자동기계 코그모")]);
+        assert!(report.error.is_none());
+        assert!(serde_json::to_string(&report).unwrap().contains(r#""selector":"p.name""#));
+
+        let report = crawl_report(&crawler, &Mode::main, Some(file.clone()), Some(String::from("p.missing")));
+        assert!(report.decks.is_empty());
+        assert!(report.to_string().contains("error    : "));
+
+        std::fs::remove_file(file).unwrap();
+    }
+}
@@ -1,11 +1,23 @@
 use serde::Deserialize;
-use std::fs;
+use std::{fs, net::SocketAddr};
+
+use crate::monitor::logging::LogFormat;
+
+use super::error::ConfigError;
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
     database : Database,
     bot : Bot,
     app : App,
+    #[serde(default)]
+    access : Access,
+    #[serde(default)]
+    watch : Watch,
+    #[serde(default)]
+    http : Http,
+    #[serde(default)]
+    webhook : Webhook,
 }
 
 #[derive(Debug, Deserialize)]
@@ -19,20 +31,98 @@ struct Database {
 
 #[derive(Debug, Deserialize)]
 struct Bot {
-    token: String
+    token: String,
+    #[serde(default)]
+    dialogue: DialogueBackend,
+}
+
+/// 대화 상태 저장소. mysql이면 재시작 후에도 진행 중인 키보드가 유지됨
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DialogueBackend {
+    Memory,
+    #[default]
+    Mysql,
+}
+
+/// 비어 있는 allowed는 모든 채팅 허용. admins는 관리자 커맨드를 쓸 수 있는 사용자 id
+#[derive(Debug, Deserialize, Default)]
+struct Access {
+    #[serde(default)]
+    allowed: Vec<i64>,
+    #[serde(default)]
+    admins: Vec<u64>,
+}
+
+/// 메타 페이지 감시. 꺼져 있으면 새 패치와 pbe 시작/종료를 확인하지 않음
+#[derive(Debug, Deserialize)]
+struct Watch {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_watch_minutes")]
+    interval_minutes: u64,
+    #[serde(default)]
+    auto_switch: bool,
+}
+
+impl Default for Watch {
+    fn default() -> Self {
+        Self { enabled: false, interval_minutes: default_watch_minutes(), auto_switch: false }
+    }
+}
+
+fn default_watch_minutes() -> u64 {
+    30
+}
+
+/// /healthz, /metrics HTTP 서버. listen이 없으면 실행하지 않음
+#[derive(Debug, Deserialize, Default)]
+struct Http {
+    #[serde(default)]
+    listen: Option<SocketAddr>,
+}
+
+/// url이 있으면 long polling 대신 웹훅으로 업데이트를 받음
+#[derive(Debug, Deserialize)]
+struct Webhook {
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default = "default_webhook_listen")]
+    listen: SocketAddr,
+    #[serde(default)]
+    secret_token: Option<String>,
+}
+
+impl Default for Webhook {
+    fn default() -> Self {
+        Self { url: None, listen: default_webhook_listen(), secret_token: None }
+    }
+}
+
+fn default_webhook_listen() -> SocketAddr {
+    SocketAddr::from(([0, 0, 0, 0], 8443))
 }
 
 #[derive(Debug, Deserialize)]
 struct App {
-    log : String
+    log : String,
+    #[serde(default)]
+    log_format : LogFormat,
+    /// 없으면 stdout
+    #[serde(default)]
+    log_file : Option<String>,
 }
 
 
+const CONFIG_PATH: &str = "./src/config/config.yaml";
+
 impl Config {
-    pub fn new() -> Self {
-        let config_content = fs::read_to_string("./src/config/config.yaml").unwrap();
-        let config: Config = serde_yaml::from_str(&config_content).unwrap();
-        config
+    pub fn new() -> Result<Self, ConfigError> {
+        let config_content = fs::read_to_string(CONFIG_PATH)
+            .map_err(|e| ConfigError::Io { path: CONFIG_PATH.to_string(), source: e })?;
+        let config: Config = serde_yaml::from_str(&config_content)
+            .map_err(|e| ConfigError::Parse { path: CONFIG_PATH.to_string(), source: e })?;
+        Ok(config)
     }
 
     pub fn token(&self) -> String {
@@ -49,9 +139,57 @@ impl Config {
         )
     }
 
+    pub fn dialogue_backend(&self) -> DialogueBackend {
+        self.bot.dialogue
+    }
+
+    pub fn allowed_ids(&self) -> &[i64] {
+        &self.access.allowed
+    }
+
+    pub fn admin_ids(&self) -> &[u64] {
+        &self.access.admins
+    }
+
+    pub fn watch_enabled(&self) -> bool {
+        self.watch.enabled
+    }
+
+    pub fn watch_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.watch.interval_minutes.max(1) * 60)
+    }
+
+    pub fn auto_switch(&self) -> bool {
+        self.watch.auto_switch
+    }
+
+    pub fn http_listen(&self) -> Option<SocketAddr> {
+        self.http.listen
+    }
+
+    pub fn webhook_url(&self) -> Option<&str> {
+        self.webhook.url.as_deref()
+    }
+
+    pub fn webhook_listen(&self) -> SocketAddr {
+        self.webhook.listen
+    }
+
+    pub fn webhook_secret_token(&self) -> Option<String> {
+        self.webhook.secret_token.clone()
+    }
+
     pub fn log_level(&self) -> &str {
         &self.app.log
     }
+
+    pub fn log_format(&self) -> LogFormat {
+        self.app.log_format
+    }
+
+    pub fn log_file(&self) -> Option<&str> {
+        self.app.log_file.as_deref()
+    }
 }
 
 
@@ -76,4 +214,52 @@ mod test {
         
         println!("{:#?}", config);
     }
+
+    #[test]
+    fn dialogue_backend_default_test() {
+        let bot: Bot = serde_yaml::from_str("token: abc").unwrap();
+        assert_eq!(bot.dialogue, DialogueBackend::Mysql);
+
+        let bot: Bot = serde_yaml::from_str("token: abc\ndialogue: memory").unwrap();
+        assert_eq!(bot.dialogue, DialogueBackend::Memory);
+    }
+
+    #[test]
+    fn http_listen_test() {
+        let http: Http = serde_yaml::from_str("listen: 127.0.0.1:9090").unwrap();
+        assert_eq!(http.listen, Some(SocketAddr::from(([127, 0, 0, 1], 9090))));
+        assert!(serde_yaml::from_str::<Http>("listen: localhost").is_err());
+        assert_eq!(Http::default().listen, None);
+    }
+
+    #[test]
+    fn app_log_test() {
+        let app: App = serde_yaml::from_str("log: info").unwrap();
+        assert_eq!(app.log_format, LogFormat::Text);
+        assert_eq!(app.log_file, None);
+
+        let app: App = serde_yaml::from_str("log: debug\nlog_format: json\nlog_file: ./bot.log").unwrap();
+        assert_eq!(app.log_format, LogFormat::Json);
+        assert_eq!(app.log_file.as_deref(), Some("./bot.log"));
+    }
+
+    #[test]
+    fn webhook_test() {
+        let webhook = Webhook::default();
+        assert_eq!(webhook.url, None);
+        assert_eq!(webhook.listen, SocketAddr::from(([0, 0, 0, 0], 8443)));
+
+        let webhook: Webhook = serde_yaml::from_str("url: https://bot.example.com/telegram\nlisten: 127.0.0.1:9000\nsecret_token: abc").unwrap();
+        assert_eq!(webhook.url.as_deref(), Some("https://bot.example.com/telegram"));
+        assert_eq!(webhook.listen, SocketAddr::from(([127, 0, 0, 1], 9000)));
+        assert_eq!(webhook.secret_token.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn watch_default_test() {
+        let watch: Watch = serde_yaml::from_str("enabled: true").unwrap();
+        assert!(watch.enabled);
+        assert_eq!(watch.interval_minutes, 30);
+        assert!(!watch.auto_switch);
+    }
 }
\ No newline at end of file
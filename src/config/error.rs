@@ -0,0 +1,25 @@
+#[derive(Debug)]
+pub enum ConfigError {
+    /// 설정 파일 읽기 실패
+    Io { path: String, source: std::io::Error },
+    /// 설정 파일 형식 오류
+    Parse { path: String, source: serde_yaml::Error },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => write!(f, "fail to read config file {}. {}", path, source),
+            ConfigError::Parse { path, source } => write!(f, "fail to parse config file {}. {}", path, source),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Parse { source, .. } => Some(source),
+        }
+    }
+}
@@ -1 +1,2 @@
-pub mod conf;
\ No newline at end of file
+pub mod conf;
+pub mod error;
\ No newline at end of file
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, future::Future, sync::{Arc, Mutex, PoisonError}, time::{Duration, Instant}};
+
+use crate::bot::traits::Mode;
+
+use super::crawl::DeckCard;
+
+/// 저장 시각과 덱 정보
+type Entry = (Instant, Arc<Vec<DeckCard>>);
+
+/// 모드별 덱 정보를 잠시 보관. 인라인 검색은 글자를 입력할 때마다 요청되므로 매번 크롤링하지 않음
+pub struct DeckCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<Mode, Entry>>,
+    /// 모드별로 한 번에 하나만 크롤링
+    loading: Mutex<HashMap<Mode, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl DeckCache {
+
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Mutex::new(HashMap::new()), loading: Mutex::new(HashMap::new()) }
+    }
+
+    /// 보관 중인 덱 정보가 없으면 load로 채움
+    /// 같은 모드를 동시에 요청하면 먼저 온 요청만 load를 실행하고 나머지는 그 결과를 사용
+    pub async fn get_or_load<F, E>(&self, mode: &Mode, load: F) -> Result<Arc<Vec<DeckCard>>, E>
+    where
+        F: Future<Output = Result<Vec<DeckCard>, E>>,
+    {
+        if let Some(cards) = self.get(mode) {
+            return Ok(cards);
+        }
+
+        let lock = self.loading.lock().unwrap_or_else(PoisonError::into_inner)
+            .entry(mode.clone())
+            .or_default()
+            .clone();
+        let _guard = lock.lock().await;
+
+        // memo. 기다리는 동안 먼저 온 요청이 채웠을 수 있음
+        if let Some(cards) = self.get(mode) {
+            return Ok(cards);
+        }
+        Ok(self.put(mode, load.await?))
+    }
+
+    /// 보관 기간이 지나지 않은 덱 정보
+    pub fn get(&self, mode: &Mode) -> Option<Arc<Vec<DeckCard>>> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.get(mode)
+            .filter(|(stored_at, _)| stored_at.elapsed() < self.ttl)
+            .map(|(_, cards)| cards.clone())
+    }
+
+    pub fn put(&self, mode: &Mode, cards: Vec<DeckCard>) -> Arc<Vec<DeckCard>> {
+        let cards = Arc::new(cards);
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.insert(mode.clone(), (Instant::now(), cards.clone()));
+        cards
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn card(name: &str) -> DeckCard {
+        DeckCard { name: name.to_string(), tier: None, champions: Vec::new() }
+    }
+
+    #[test]
+    fn cache_test() {
+        let cache = DeckCache::new(Duration::from_secs(60));
+        assert!(cache.get(&Mode::main).is_none());
+
+        cache.put(&Mode::main, vec![card("A")]);
+
+        assert_eq!(cache.get(&Mode::main).unwrap()[0].name, "A");
+        assert!(cache.get(&Mode::pbe).is_none());
+    }
+
+    #[test]
+    fn expired_cache_test() {
+        let cache = DeckCache::new(Duration::ZERO);
+        cache.put(&Mode::main, vec![card("A")]);
+
+        assert!(cache.get(&Mode::main).is_none());
+    }
+
+    #[tokio::test]
+    async fn single_flight_test() {
+        let cache = Arc::new(DeckCache::new(Duration::from_secs(60)));
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        let requests = (0..10).map(|_| {
+            let (cache, loads) = (cache.clone(), loads.clone());
+            tokio::spawn(async move {
+                cache.get_or_load(&Mode::main, async {
+                    loads.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok::<_, String>(vec![card("A")])
+                }).await
+            })
+        }).collect::<Vec<_>>();
+
+        for request in requests {
+            assert_eq!(request.await.unwrap().unwrap()[0].name, "A");
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn load_error_test() {
+        let cache = DeckCache::new(Duration::from_secs(60));
+        assert!(cache.get_or_load(&Mode::main, async { Err::<Vec<DeckCard>, _>("crawl error") }).await.is_err());
+        // memo. 실패한 결과는 보관하지 않음
+        assert!(cache.get(&Mode::main).is_none());
+    }
+}
@@ -1,18 +1,30 @@
 use reqwest::blocking::get;
-use scraper::{Html, Selector};
-use std::{error::Error, fmt::format};
+use scraper::{ElementRef, Html, Selector};
+use std::{error::Error, fmt::format, sync::Arc, time::Instant};
 use regex::Regex;
-use crate::bot::traits::Mode;
+use arc_swap::ArcSwap;
+use crate::{bot::traits::Mode, monitor::metrics::METRICS};
 
 use super::error::CrawlError;
 
-#[derive(Clone)]
+/// css path는 ArcSwap으로 보관하여 조회 중인 요청을 막지 않고 교체
 pub struct LolcheggCrawler {
     main_url: &'static str,
-	css_path: String,
+	css_path: ArcSwap<String>,
     path_finder: CssPathFinder,
 }
 
+/// 인라인 검색 결과로 보여줄 덱 정보. 페이지에서 찾지 못한 값은 비워둠
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckCard {
+    pub name: String,
+    pub tier: Option<String>,
+    pub champions: Vec<String>,
+}
+
+/// 덱 한 줄에서 보여줄 최대 챔피언 수
+const MAX_CHAMPIONS: usize = 10;
+
 #[derive(Clone)]
 struct CssPathFinder {
     tag: Regex,
@@ -22,55 +34,93 @@ struct CssPathFinder {
 
 impl LolcheggCrawler {
     pub fn new() -> Self {
-        log::info!("HI - new");
         let crawler = Self { // TODO. URL들 다 config로 빼고 주입 받기
             main_url: "https://lolchess.gg/meta",
-            css_path : String::from("#content-container > section > div.css-s9pipd.e2kj5ne0 > div > div > div > div.css-5x9ld.emls75t2 > div.css-35tzvc.emls75t4 > div"), 
+            css_path : ArcSwap::from_pointee(String::from("#content-container > section > div.css-s9pipd.e2kj5ne0 > div > div > div > div.css-5x9ld.emls75t2 > div.css-35tzvc.emls75t4 > div")), 
             path_finder : CssPathFinder { 
                     tag: Regex::new(r"^[^<]*<([^>]+)>.*$").unwrap(), 
                     class: Regex::new(r#"id="([^"]+)""#).unwrap(), 
                     id: Regex::new(r#"class="([^"]+)""#).unwrap(),
                 }
         };
+        tracing::debug!(url = crawler.main_url, selector = %crawler.css_path.load(), "crawler created");
         crawler
     }
 
     pub fn recommended_deck(&self, mode: &Mode) -> Result<Vec<String>, CrawlError> {
-        match *mode {
+        timed(|| match *mode {
             Mode::main => self.get_main_dec(),
             Mode::pbe => self.get_pbe_dec(),
-        }
+        })
     } 
 
-    pub fn update_css_path(&mut self) -> Result<(), CrawlError> {
-        let path: String = self.path_finder.css_path(self.main_url, "초반 빌드업 요약").unwrap();
-        
-        if crawl(self.main_url, &path).is_err() {
-            Err(format!("잘못된 css path 결과"))?
+    /// 덱 이름과 함께 티어, 챔피언 구성을 가져옴
+    pub fn deck_cards(&self, mode: &Mode) -> Result<Vec<DeckCard>, CrawlError> {
+        let (url, selector) = (self.url(mode), self.css_path());
+        let started = Instant::now();
+
+        let result = timed(|| select_cards(&document(&url)?, &selector));
+        log_crawl(&url, &selector, started, &result);
+        result
+    }
+
+    /// selector를 지정하면 현재 css path 대신 사용. 경로는 교체하지 않음
+    pub fn deck_names(&self, mode: &Mode, selector: Option<&str>) -> Result<Vec<String>, CrawlError> {
+        crawl(&self.url(mode), selector.unwrap_or(&self.css_path.load()))
+    }
+
+    pub fn url(&self, mode: &Mode) -> String {
+        match *mode {
+            Mode::main => self.main_url.to_string(),
+            Mode::pbe => format!("{}?pbe=true", self.main_url),
         }
+    }
 
-        self.css_path = path;
+    pub fn css_path(&self) -> String {
+        self.css_path.load().to_string()
+    }
+
+    /// 메타 페이지에 표시된 패치 버전. 예) 14.24
+    pub fn patch_version(&self) -> Result<Option<String>, CrawlError> {
+        find_patch_version(&document(self.main_url)?)
+    }
+
+    // memo. 탐색과 검증은 잠금 없이 진행하고, 검증된 경로만 원자적으로 교체
+    pub fn update_css_path(&self) -> Result<(), CrawlError> {
+        let path: String = self.discover_css_path()?;
+
+        self.css_path.store(Arc::new(path));
        
-       return Ok(());
+       Ok(())
+    }
+
+    /// 페이지에서 덱 목록 css path를 새로 찾고, 덱이 조회되는 경로만 반환
+    pub fn discover_css_path(&self) -> Result<String, CrawlError> {
+        let path: String = self.path_finder.css_path(self.main_url, "초반 빌드업 요약")?;
+
+        crawl(self.main_url, &path)?;
+
+        Ok(path)
     }
 
     fn get_main_dec(&self) -> Result<Vec<String>, CrawlError> {
-        crawl(self.main_url, &self.css_path)
+        crawl(self.main_url, &self.css_path.load())
     }
     fn get_pbe_dec(&self) -> Result<Vec<String>, CrawlError> {
-       crawl(&format!("{}?pbe=true",self.main_url ), &self.css_path)
+       crawl(&format!("{}?pbe=true",self.main_url ), &self.css_path.load())
     }
 
 }
 
 impl CssPathFinder {
 
-    fn css_path(&self, url: &str, target:&str) -> Result<String, String> {
+    fn css_path(&self, url: &str, target:&str) -> Result<String, CrawlError> {
 
         // Parse the HTML content
-        let document = document(url).unwrap(); // todo
+        let document = document(url)?;
         // Define a basic selector that selects all elements
-        let selector = Selector::parse("div:not(:has(*))").unwrap();
+        let selector = Selector::parse("div:not(:has(*))")
+                            .map_err(|e| CrawlError::Selector(format!("{:?}", e)))?;
     
         let mut path = String::new();
         // Traverse all elements to find one that contains the target string
@@ -79,7 +129,8 @@ impl CssPathFinder {
     
             if element_text.contains(target) {
                 let tag = format!("{:?}", element.value());
-                path = self.css_format_converter(&tag).unwrap();
+                path = self.css_format_converter(&tag)
+                            .ok_or_else(|| CrawlError::PathNotFound(target.to_string()))?;
                 
                 for ancestor in element.ancestors() {
                     let ancestor_tag = format!("{:?}", ancestor.value());    
@@ -89,6 +140,10 @@ impl CssPathFinder {
                 } 
             }
         }
+
+        if path.is_empty() {
+            return Err(CrawlError::PathNotFound(target.to_string()));
+        }
         Ok(path)
     }
 
@@ -117,15 +172,26 @@ impl CssPathFinder {
 
 
 
+/// 크롤링 소요 시간과 성공 여부를 지표로 남김
+fn timed<T>(f: impl FnOnce() -> Result<T, CrawlError>) -> Result<T, CrawlError> {
+    let started = Instant::now();
+    let result = f();
+    METRICS.crawl(started.elapsed(), result.is_ok());
+    result
+}
+
 fn document(url: &str) -> Result<Html, CrawlError> {
     // Fetch the URL content
     let client = reqwest::blocking::Client::new();
     let response = client.get(url)
         .header(reqwest::header::USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-        .send()
-        .map_err(|e| format!("Fail to get url. {:?}", e))?
-        .text()
-        .map_err(|e| format!("Fail to get response text. {:?}", e))?;
+        .send()?;
+
+    if !response.status().is_success() {
+        return Err(CrawlError::HttpStatus(response.status()));
+    }
+
+    let response = response.text()?;
 
 
     // Return parsed HTML document
@@ -133,28 +199,96 @@ fn document(url: &str) -> Result<Html, CrawlError> {
 }
 
 fn crawl(url: &str, path: &str) -> Result<Vec<String>, CrawlError> {
-    log::info!("HI\n{url}\n{path}");
+    let started = Instant::now();
     // Parse the HTML document
-    let document = document(url)?;
+    let result = document(url).and_then(|document| Ok(select_elements(&document, path)?
+        .into_iter()
+        .map(|element| element.text().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<String>>()));
+
+    log_crawl(url, path, started, &result);
+    result
+}
+
+fn log_crawl<T>(url: &str, selector: &str, started: Instant, result: &Result<Vec<T>, CrawlError>) {
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(decks) => tracing::info!(url, selector, elapsed_ms, decks = decks.len(), "crawled decks"),
+        Err(e) => tracing::warn!(url, selector, elapsed_ms, error = %e, "fail to crawl decks"),
+    }
+}
+
+/// 저장해 둔 html에서 덱 이름 조회
+pub fn parse_decks(html: &str, path: &str) -> Result<Vec<String>, CrawlError> {
+    let document = Html::parse_document(html);
+
+    Ok(select_elements(&document, path)?
+        .into_iter()
+        .map(|element| element.text().collect::<Vec<_>>().join(" "))
+        .collect())
+}
 
+fn select_elements<'a>(document: &'a Html, path: &str) -> Result<Vec<ElementRef<'a>>, CrawlError> {
     // Create a selector for the CSS path
     let selector = Selector::parse(path)
-                            .map_err(|e| format!("Fail to parse {:?}", e))?;
+                            .map_err(|e| CrawlError::Selector(format!("{:?}", e)))?;
 
-    let mut result: Vec<String> = Vec::new();
-    // Find and iterate over matching elements
-    for element in document.select(&selector) {
-        // Extract and print the element's inner text
-        result.push(element.text().collect::<Vec<_>>().join(" "));
+    let result: Vec<ElementRef> = document.select(&selector).collect();
+    if result.is_empty() {
+        return Err(CrawlError::EmptyResult);
     }
 
-    if result.len() == 0 {
-        return Err(format!("조회 결과 없음 오류"))? 
-   }
-
     Ok(result)
 }
 
+fn find_patch_version(document: &Html) -> Result<Option<String>, CrawlError> {
+    let version = Regex::new(r"(?i)(?:패치|patch)\s*(\d{1,2}\.\d{1,2}[a-z]?)").map_err(|e| CrawlError::Selector(e.to_string()))?;
+    let text = document.root_element().text().collect::<Vec<_>>().join(" ");
+    Ok(version.captures(&text).map(|cap| cap[1].to_string()))
+}
+
+// memo. 덱 이름 요소의 조상 중 챔피언 이미지가 여러 개 있는 가장 가까운 요소를 덱 한 줄로 봄
+fn select_cards(document: &Html, path: &str) -> Result<Vec<DeckCard>, CrawlError> {
+    let image = Selector::parse("img[alt]").map_err(|e| CrawlError::Selector(format!("{:?}", e)))?;
+    let any = Selector::parse("*").map_err(|e| CrawlError::Selector(format!("{:?}", e)))?;
+    let tier = Regex::new(r"^[SABCD][+-]?$").map_err(|e| CrawlError::Selector(e.to_string()))?;
+
+    let deck = Selector::parse(path).map_err(|e| CrawlError::Selector(format!("{:?}", e)))?;
+
+    let cards = select_elements(document, path)?
+        .into_iter()
+        .map(|element| {
+            let name = element.text().collect::<Vec<_>>().join(" ");
+            // memo. 다른 덱 이름까지 포함하는 조상은 덱 한 줄이 아님
+            let row = element.ancestors()
+                .filter_map(ElementRef::wrap)
+                .take_while(|ancestor| ancestor.select(&deck).count() == 1)
+                .find(|ancestor| ancestor.select(&image).count() >= 3);
+
+            let Some(row) = row else {
+                return DeckCard { name, tier: None, champions: Vec::new() };
+            };
+
+            let tier = row.select(&any)
+                .filter(|e| e.children().all(|child| !child.value().is_element()))
+                .map(|e| e.text().collect::<String>().trim().to_string())
+                .find(|text| tier.is_match(text));
+
+            let mut champions: Vec<String> = Vec::new();
+            for alt in row.select(&image).filter_map(|img| img.value().attr("alt")) {
+                let alt = alt.trim();
+                if !alt.is_empty() && !champions.iter().any(|c| c == alt) && champions.len() < MAX_CHAMPIONS {
+                    champions.push(alt.to_string());
+                }
+            }
+
+            DeckCard { name, tier, champions }
+        })
+        .collect();
+
+    Ok(cards)
+}
+
 
 #[cfg(test)]
 mod test {
@@ -163,7 +297,7 @@ mod test {
 	#[test]
 	fn new_test(){
 		let crawler = LolcheggCrawler::new();
-		print!("{}", crawler.css_path)
+		print!("{}", crawler.css_path.load())
 	}
 
     #[test]
@@ -178,6 +312,45 @@ mod test {
         // is_err를 사용해서 테스트 하면 간결. assert!(crawl(url, path).is_err());
     }
 
+    #[test]
+    fn select_cards_test() {
+        let html = r#"
+            <div class="deck">
+                <span>S</span>
+                <div class="name">리롤 징크스</div>
+                <img alt="징크스"><img alt="바이"><img alt="징크스"><img alt="실코">
+            </div>
+            <div class="deck">
+                <div class="name">외톨이 덱</div>
+            </div>"#;
+        let document = Html::parse_document(html);
+
+        let cards = select_cards(&document, "div.name").unwrap();
+
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0], DeckCard {
+            name: String::from("리롤 징크스"),
+            tier: Some(String::from("S")),
+            champions: vec![String::from("징크스"), String::from("바이"), String::from("실코")],
+        });
+        assert_eq!(cards[1].tier, None);
+        assert!(cards[1].champions.is_empty());
+
+        assert!(matches!(select_cards(&document, "div.missing"), Err(CrawlError::EmptyResult)));
+    }
+
+    #[test]
+    fn find_patch_version_test() {
+        let document = Html::parse_document("<div><span>메타 덱</span><span>패치 14.24b 기준</span></div>");
+        assert_eq!(find_patch_version(&document).unwrap(), Some(String::from("14.24b")));
+
+        let document = Html::parse_document("<div>Patch  15.1</div>");
+        assert_eq!(find_patch_version(&document).unwrap(), Some(String::from("15.1")));
+
+        let document = Html::parse_document("<div>14.24</div>");
+        assert_eq!(find_patch_version(&document).unwrap(), None);
+    }
+
     #[test]
     fn get_test() {
         let url = "https://lolchess.gg/meta"; // Replace with your URL
@@ -193,8 +366,7 @@ mod test {
 
     #[test]
     fn crawler_test() {
-        std::env::set_var("RUST_LOG", "info");
-        pretty_env_logger::init();
+        let _ = tracing_subscriber::fmt().with_env_filter("info").try_init();
 
         let crawler = LolcheggCrawler::new();
         match crawler.get_main_dec() {
@@ -1,17 +1,57 @@
 #[derive(Debug)]
-pub struct CrawlError(String);
+pub enum CrawlError {
+    /// 요청 전송 또는 응답 본문 수신 실패
+    Network(reqwest::Error),
+    /// 성공이 아닌 HTTP 응답
+    HttpStatus(reqwest::StatusCode),
+    /// css selector 파싱 실패
+    Selector(String),
+    /// selector에 해당하는 요소가 없음
+    EmptyResult,
+    /// 페이지에서 기준 문구를 찾지 못해 css path를 만들 수 없음
+    PathNotFound(String),
+}
+
+impl CrawlError {
+    /// 잠시 후 다시 시도하면 성공할 수 있는 오류
+    pub fn is_transient(&self) -> bool {
+        match self {
+            CrawlError::Network(_) => true,
+            CrawlError::HttpStatus(status) => status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+            _ => false,
+        }
+    }
+
+    /// 사이트 구조 변경으로 css path 갱신이 필요한 오류
+    pub fn is_broken_path(&self) -> bool {
+        matches!(self, CrawlError::Selector(_) | CrawlError::EmptyResult)
+    }
+}
 
 impl std::fmt::Display for CrawlError {
     // '_는 익명 수명을 나타내며, 컴파일러가 자동으로 추론하는 수명을 명시적으로 나타냄
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            CrawlError::Network(e) => write!(f, "Fail to get url. {}", e),
+            CrawlError::HttpStatus(status) => write!(f, "Unexpected http status. {}", status),
+            CrawlError::Selector(e) => write!(f, "Fail to parse {}", e),
+            CrawlError::EmptyResult => write!(f, "조회 결과 없음 오류"),
+            CrawlError::PathNotFound(target) => write!(f, "css path 탐색 실패. '{}' 문구 없음", target),
+        }
     }
 }
 
-impl std::error::Error for CrawlError{}
+impl std::error::Error for CrawlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CrawlError::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
-impl From<String> for CrawlError {
-    fn from(msg: String) -> Self{
-        Self(msg)
+impl From<reqwest::Error> for CrawlError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Network(e)
     }
 }
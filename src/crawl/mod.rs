@@ -1,2 +1,3 @@
+pub mod cache;
 pub mod crawl;
 pub mod error;
\ No newline at end of file
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use mysql::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use teloxide::{dispatching::dialogue::Storage as DialogueStore, types::ChatId};
+
+use super::{db::Storage, error::StorageError};
+
+/// 봇 재시작 후에도 대화 상태가 유지되도록 dialogue 테이블에 상태를 JSON으로 저장
+pub struct DialogueStorage {
+    stg: Storage,
+}
+
+impl DialogueStorage {
+
+    pub fn new(stg: &Storage) -> Result<Arc<Self>, StorageError> {
+        let dialogue = DialogueStorage { stg: stg.clone() };
+        dialogue.create()?;
+        Ok(Arc::new(dialogue))
+    }
+
+    fn create(&self) -> Result<(), StorageError> {
+        let mut conn = self.stg.conn()?;
+        conn.query_drop(r"
+            CREATE TABLE IF NOT EXISTS dialogue (
+            chat_id BIGINT PRIMARY KEY,
+            state TEXT NOT NULL
+        )")?;
+        Ok(())
+    }
+
+    fn delete(&self, chat_id: ChatId) -> Result<(), StorageError> {
+        let mut conn = self.stg.conn()?;
+        conn.exec_drop(r"
+            DELETE FROM dialogue
+            WHERE chat_id = :chat_id",
+            (chat_id.0,)
+        )?;
+        Ok(())
+    }
+
+    fn upsert(&self, chat_id: ChatId, state: &str) -> Result<(), StorageError> {
+        let mut conn = self.stg.conn()?;
+        conn.exec_drop(r"
+            INSERT INTO dialogue (chat_id, state)
+            VALUES (:chat_id, :state)
+            ON DUPLICATE KEY UPDATE
+            state = :state",
+            (chat_id.0, state, state) // memo. only supports positional placeholders
+        )?;
+        Ok(())
+    }
+
+    fn select(&self, chat_id: ChatId) -> Result<Option<String>, StorageError> {
+        let mut conn = self.stg.conn()?;
+        let result: Option<String> = conn.exec_first(r"
+            SELECT state
+            FROM dialogue
+            WHERE chat_id = :chat_id",
+            (chat_id.0,)
+        )?;
+        Ok(result)
+    }
+}
+
+// memo. mysql 클라이언트는 blocking이므로 spawn_blocking 안에서 호출
+impl<D> DialogueStore<D> for DialogueStorage
+where
+    D: Serialize + DeserializeOwned + Send + 'static,
+{
+    type Error = StorageError;
+
+    fn remove_dialogue(self: Arc<Self>, chat_id: ChatId) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || self.delete(chat_id)).await?
+        })
+    }
+
+    fn update_dialogue(self: Arc<Self>, chat_id: ChatId, dialogue: D) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let state = serde_json::to_string(&dialogue)?;
+            tokio::task::spawn_blocking(move || self.upsert(chat_id, &state)).await?
+        })
+    }
+
+    fn get_dialogue(self: Arc<Self>, chat_id: ChatId) -> BoxFuture<'static, Result<Option<D>, Self::Error>> {
+        Box::pin(async move {
+            let state = tokio::task::spawn_blocking(move || self.select(chat_id)).await??;
+            match state {
+                // memo. 이전 버전에서 저장한 상태를 읽지 못하면 대화를 처음부터 시작
+                Some(s) => match serde_json::from_str(&s) {
+                    Ok(state) => Ok(Some(state)),
+                    Err(e) => {
+                        log::warn!("drop unreadable dialogue state. chat: {}, error: {}", chat_id, e);
+                        Ok(None)
+                    }
+                },
+                None => Ok(None),
+            }
+        })
+    }
+}
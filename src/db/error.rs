@@ -0,0 +1,53 @@
+#[derive(Debug)]
+pub enum StorageError {
+    /// 커넥션 풀 생성 또는 커넥션 획득 실패
+    Connection(mysql::Error),
+    /// 쿼리 실행 실패
+    Query(mysql::Error),
+    /// 저장된 값의 직렬화/역직렬화 실패
+    Serde(serde_json::Error),
+    /// blocking 작업 스레드 실패
+    Task(tokio::task::JoinError),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Connection(e) => write!(f, "db connection error. {}", e),
+            StorageError::Query(e) => write!(f, "db query error. {}", e),
+            StorageError::Serde(e) => write!(f, "db (de)serialize error. {}", e),
+            StorageError::Task(e) => write!(f, "db task error. {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Connection(e) => Some(e),
+            StorageError::Query(e) => Some(e),
+            StorageError::Serde(e) => Some(e),
+            StorageError::Task(e) => Some(e),
+        }
+    }
+}
+
+// memo. 커넥션 획득은 Storage::conn에서 Connection으로 변환하므로 나머지는 쿼리 오류
+impl From<mysql::Error> for StorageError {
+    fn from(e: mysql::Error) -> Self {
+        crate::monitor::metrics::METRICS.db_error();
+        Self::Query(e)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serde(e)
+    }
+}
+
+impl From<tokio::task::JoinError> for StorageError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        Self::Task(e)
+    }
+}
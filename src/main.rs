@@ -2,28 +2,129 @@ mod crawl;
 mod db;
 mod bot;
 mod config;
+mod cli;
+mod monitor;
 
 use crawl::crawl::LolcheggCrawler;
 use db::db::Storage;
+use db::dialogue::DialogueStorage;
+use bot::error::BotError;
 use bot::bot::LolcheBot;
-use config::conf::Config;
+use bot::access::AccessControl;
+use bot::watcher::WatchOptions;
+use bot::webhook::WebhookOptions;
+use monitor::logging;
+use config::conf::{Config, DialogueBackend};
+use teloxide::dispatching::dialogue::{InMemStorage, Storage as _};
+use std::process::ExitCode;
+use clap::Parser;
+use cli::cli::Cli;
 
 #[tokio::main]
-async fn main() {
-    
-    let config = Config::new();
+async fn main() -> ExitCode {
+
+    let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        return admin(command).await;
+    }
+
+    let config = match Config::new() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Lolche Bot 시작 실패. {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(e) = logging::init(config.log_level(), config.log_format(), config.log_file()) {
+        eprintln!("Lolche Bot 시작 실패. 로그 설정 오류. {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    // memo. 설정이 잘못되면 long polling으로 대신 실행하지 않고 종료
+    let webhook = match config.webhook_url() {
+        Some(url) => match WebhookOptions::new(config.webhook_listen(), url, config.webhook_secret_token()) {
+            Ok(options) => Some(options),
+            Err(e) => {
+                log::error!("Lolche Bot 시작 실패. {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+
+    if let Err(e) = start(config, webhook).await {
+        log::error!("Lolche Bot 시작 실패. {}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
 
-    std::env::set_var("RUST_LOG", config.log_level());
-    pretty_env_logger::init();
+/// 관리 커맨드 실행. mysql, reqwest blocking 클라이언트를 쓰므로 blocking 스레드에서 실행
+async fn admin(command: cli::cli::Command) -> ExitCode {
 
+    let needs_storage = command.needs_storage();
+    let config = match Config::new() {
+        Ok(config) => Some(config),
+        Err(e) if needs_storage => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+        Err(_) => None,
+    };
+
+    // memo. 관리 커맨드의 결과는 stdout으로 출력하므로 로그는 stderr로
+    let level = config.as_ref().map(|config| config.log_level()).unwrap_or("warn");
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::try_new(level).unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn")))
+        .with_writer(std::io::stderr)
+        .try_init();
+
+    let result = tokio::task::spawn_blocking(move || {
+        let stg = match &config {
+            Some(config) if needs_storage => Some(Storage::new(&config.db_url())?),
+            _ => None,
+        };
+        cli::cli::run(command, stg)
+    }).await;
+
+    match result {
+        Ok(Ok(())) => ExitCode::SUCCESS,
+        Ok(Err(e)) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn start(config: Config, webhook: Option<WebhookOptions>) -> Result<(), BotError> {
 
     let lolchegg_crawler = LolcheggCrawler::new();
-    let stg = Storage::new(&config.db_url()); // memo. config.db_url()의 결과값이 String을 소유하고 있으며, main 블록이 끝나면 소멸됨
+    let stg = Storage::new(&config.db_url())?; // memo. config.db_url()의 결과값이 String을 소유하고 있으며, main 블록이 끝나면 소멸됨
 
+    let dialogue = match config.dialogue_backend() {
+        DialogueBackend::Memory => InMemStorage::new().erase(),
+        DialogueBackend::Mysql => DialogueStorage::new(&stg)?.erase(),
+    };
+    
+    let access = AccessControl::new(config.allowed_ids(), config.admin_ids());
     
-    let my_bot = LolcheBot::new(config.token(), lolchegg_crawler,stg);
+    let mut my_bot = LolcheBot::new(config.token(), lolchegg_crawler,stg, dialogue, access);
+    if config.watch_enabled() {
+        my_bot = my_bot.watch(WatchOptions { interval: config.watch_interval(), auto_switch: config.auto_switch() });
+    }
+    if let Some(addr) = config.http_listen() {
+        my_bot = my_bot.http(addr);
+    }
+    if let Some(options) = webhook {
+        my_bot = my_bot.webhook(options);
+    }
 
     log::info!("Lolche Bot Started!");
 
-    my_bot.run().await;
+    my_bot.run().await
 }
@@ -0,0 +1,51 @@
+use std::{fs::OpenOptions, sync::Arc};
+
+use serde::Deserialize;
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, EnvFilter};
+
+/// 로그 출력 형식. json은 한 줄에 하나의 json 객체로, 현재 span의 필드를 함께 기록
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// level은 RUST_LOG와 같은 형식. 예) info, lolche_bot_rust=debug
+/// file이 없으면 stdout으로 출력
+pub fn init(level: &str, format: LogFormat, file: Option<&str>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let filter = EnvFilter::try_new(level)?;
+
+    let writer = match file {
+        Some(path) => BoxMakeWriter::new(Arc::new(OpenOptions::new().create(true).append(true).open(path)?)),
+        None => BoxMakeWriter::new(std::io::stdout),
+    };
+
+    // memo. log 크레이트로 남긴 로그도 tracing 이벤트로 변환되어 현재 span의 필드와 함께 기록됨
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_ansi(file.is_none());
+
+    match format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().with_current_span(true).with_span_list(false).try_init(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn log_format_test() {
+        assert_eq!(serde_yaml::from_str::<LogFormat>("json").unwrap(), LogFormat::Json);
+        assert!(serde_yaml::from_str::<LogFormat>("xml").is_err());
+    }
+
+    #[test]
+    fn invalid_level_test() {
+        assert!(init("info,=[", LogFormat::Text, None).is_err());
+    }
+}
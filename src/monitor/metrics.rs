@@ -0,0 +1,132 @@
+use std::{collections::BTreeMap, fmt::Write, sync::{atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering}, Mutex}, time::Duration};
+
+/// 프로세스 전체에서 공유하는 지표
+// memo. 크롤러와 저장소 안쪽에서도 기록하므로 의존성 주입 대신 전역으로 둠
+pub static METRICS: Metrics = Metrics::new();
+
+#[derive(Debug)]
+pub struct Metrics {
+    commands: Mutex<BTreeMap<String, u64>>,
+    crawls: AtomicU64,
+    crawl_failures: AtomicU64,
+    crawl_millis: AtomicU64,
+    /// 마지막으로 크롤링에 성공한 unix 시각. 0이면 성공한 적 없음
+    last_crawl_success: AtomicI64,
+    db_errors: AtomicU64,
+    dispatcher_running: AtomicBool,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+
+    pub const fn new() -> Self {
+        Self {
+            commands: Mutex::new(BTreeMap::new()),
+            crawls: AtomicU64::new(0),
+            crawl_failures: AtomicU64::new(0),
+            crawl_millis: AtomicU64::new(0),
+            last_crawl_success: AtomicI64::new(0),
+            db_errors: AtomicU64::new(0),
+            dispatcher_running: AtomicBool::new(false),
+        }
+    }
+
+    pub fn command(&self, name: &str) {
+        if let Ok(mut commands) = self.commands.lock() {
+            *commands.entry(name.to_string()).or_default() += 1;
+        }
+    }
+
+    pub fn crawl(&self, elapsed: Duration, success: bool) {
+        self.crawls.fetch_add(1, Ordering::Relaxed);
+        self.crawl_millis.fetch_add(elapsed.as_millis() as u64, Ordering::Relaxed);
+        if success {
+            self.last_crawl_success.store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+        } else {
+            self.crawl_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn db_error(&self) {
+        self.db_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_dispatcher_running(&self, running: bool) {
+        self.dispatcher_running.store(running, Ordering::Relaxed);
+    }
+
+    pub fn dispatcher_running(&self) -> bool {
+        self.dispatcher_running.load(Ordering::Relaxed)
+    }
+
+    /// unix 시각
+    pub fn last_crawl_success(&self) -> Option<i64> {
+        Some(self.last_crawl_success.load(Ordering::Relaxed)).filter(|at| *at > 0)
+    }
+
+    /// Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP lolche_commands_total Commands handled per type.");
+        let _ = writeln!(out, "# TYPE lolche_commands_total counter");
+        if let Ok(commands) = self.commands.lock() {
+            for (name, count) in commands.iter() {
+                let _ = writeln!(out, "lolche_commands_total{{command=\"{}\"}} {}", name, count);
+            }
+        }
+
+        let crawls = self.crawls.load(Ordering::Relaxed);
+        let _ = writeln!(out, "# HELP lolche_crawl_duration_seconds Time spent crawling the meta page.");
+        let _ = writeln!(out, "# TYPE lolche_crawl_duration_seconds summary");
+        let _ = writeln!(out, "lolche_crawl_duration_seconds_sum {:.3}", self.crawl_millis.load(Ordering::Relaxed) as f64 / 1000.0);
+        let _ = writeln!(out, "lolche_crawl_duration_seconds_count {}", crawls);
+
+        let _ = writeln!(out, "# HELP lolche_crawl_failures_total Crawls that returned an error.");
+        let _ = writeln!(out, "# TYPE lolche_crawl_failures_total counter");
+        let _ = writeln!(out, "lolche_crawl_failures_total {}", self.crawl_failures.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP lolche_last_crawl_success_timestamp_seconds Unix time of the last successful crawl.");
+        let _ = writeln!(out, "# TYPE lolche_last_crawl_success_timestamp_seconds gauge");
+        let _ = writeln!(out, "lolche_last_crawl_success_timestamp_seconds {}", self.last_crawl_success.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP lolche_db_errors_total Database connection and query errors.");
+        let _ = writeln!(out, "# TYPE lolche_db_errors_total counter");
+        let _ = writeln!(out, "lolche_db_errors_total {}", self.db_errors.load(Ordering::Relaxed));
+
+        let _ = writeln!(out, "# HELP lolche_dispatcher_up Whether the telegram dispatcher is running.");
+        let _ = writeln!(out, "# TYPE lolche_dispatcher_up gauge");
+        let _ = writeln!(out, "lolche_dispatcher_up {}", self.dispatcher_running() as u8);
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_test() {
+        let metrics = Metrics::new();
+        metrics.command("update");
+        metrics.command("update");
+        metrics.command("done");
+        metrics.crawl(Duration::from_millis(1500), true);
+        metrics.crawl(Duration::from_millis(500), false);
+        metrics.db_error();
+
+        let text = metrics.render();
+        assert!(text.contains("lolche_commands_total{command=\"done\"} 1\nlolche_commands_total{command=\"update\"} 2\n"));
+        assert!(text.contains("lolche_crawl_duration_seconds_sum 2.000\nlolche_crawl_duration_seconds_count 2\n"));
+        assert!(text.contains("lolche_crawl_failures_total 1\n"));
+        assert!(text.contains("lolche_db_errors_total 1\n"));
+        assert!(text.contains("lolche_dispatcher_up 0\n"));
+        assert!(metrics.last_crawl_success().is_some());
+    }
+}
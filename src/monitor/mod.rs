@@ -0,0 +1,3 @@
+pub mod logging;
+pub mod metrics;
+pub mod server;
\ No newline at end of file
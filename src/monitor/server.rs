@@ -0,0 +1,76 @@
+use std::net::SocketAddr;
+
+use axum::{extract::State, http::{header, StatusCode}, response::IntoResponse, routing::get, Json, Router};
+use serde::Serialize;
+
+use crate::db::db::Storage;
+
+use super::metrics::METRICS;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Health {
+    pub db: bool,
+    pub dispatcher: bool,
+    /// unix 시각. 크롤링은 사이트 상태에 따라 실패할 수 있으므로 정상 여부에는 반영하지 않음
+    pub last_crawl_success: Option<i64>,
+}
+
+impl Health {
+    pub fn is_healthy(&self) -> bool {
+        self.db && self.dispatcher
+    }
+}
+
+pub fn router(stg: Storage) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/metrics", get(metrics))
+        .with_state(stg)
+}
+
+/// 봇과 함께 실행되는 HTTP 서버. 실패해도 봇은 계속 동작
+pub async fn serve(addr: SocketAddr, stg: Storage) {
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("fail to bind http server on {}. {}", addr, e);
+            return;
+        }
+    };
+    log::info!("http server listening on {}", addr);
+
+    if let Err(e) = axum::serve(listener, router(stg)).await {
+        log::error!("http server stopped. {}", e);
+    }
+}
+
+async fn healthz(State(stg): State<Storage>) -> impl IntoResponse {
+    let db = tokio::task::spawn_blocking(move || stg.ping())
+        .await
+        .map(|result| result.map_err(|e| log::warn!("health check failed to reach db. {}", e)).is_ok())
+        .unwrap_or(false);
+
+    let health = Health {
+        db,
+        dispatcher: METRICS.dispatcher_running(),
+        last_crawl_success: METRICS.last_crawl_success(),
+    };
+    let status = if health.is_healthy() { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(health))
+}
+
+async fn metrics() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], METRICS.render())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn health_test() {
+        assert!(Health { db: true, dispatcher: true, last_crawl_success: None }.is_healthy());
+        assert!(!Health { db: true, dispatcher: false, last_crawl_success: Some(1) }.is_healthy());
+        assert!(!Health { db: false, dispatcher: true, last_crawl_success: Some(1) }.is_healthy());
+    }
+}